@@ -0,0 +1,493 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::TryRecvError,
+    time::Duration,
+};
+
+use rand::SeedableRng as _;
+use rand_chacha::ChaCha8Rng;
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+};
+use yatzy::{Combo, Die, Game};
+use yatzy_solver::Choice;
+
+use crate::{
+    command::{Command, ParseCommandError, reroll_for_keep},
+    hints::{Analysis, Worker, WorkerEvent, choice_text},
+    save::{HistoryEntry, RngState, SavedGame},
+    ui,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub expected_values_path: Option<PathBuf>,
+    pub hints: bool,
+    pub coach_threshold: Option<f64>,
+    pub seed: Option<u64>,
+    pub resume: Option<(SavedGame, Game)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Command,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    None,
+    Info(String),
+    Warning(String),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct App {
+    pub game: Game,
+    pub selected: [bool; 5],
+    pub cursor: usize,
+    pub mode: Mode,
+    pub input: String,
+    pub status: Status,
+    pub hints: bool,
+    pub show_help: bool,
+    rng: ChaCha8Rng,
+    seed: Option<u64>,
+    history: Vec<HistoryEntry>,
+    quit: bool,
+    worker: Option<Worker>,
+    loaded: bool,
+    analyses: HashMap<Game, Analysis>,
+    requested: HashSet<Game>,
+    pending_hint: Option<Game>,
+    pending_coach: Vec<(Game, Choice)>,
+    coach_threshold: Option<f64>,
+}
+
+impl App {
+    pub fn new(options: Options) -> Self {
+        let mut rng = match options.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_os_rng(),
+        };
+        let mut seed = options.seed;
+        let (game, history) = match options.resume {
+            Some((saved, game)) => {
+                if let Some(state) = saved.rng {
+                    rng = restore_rng(state);
+                    seed = Some(state.seed);
+                }
+                (game, saved.history)
+            }
+            None => (Game::new_random(&mut rng), Vec::new()),
+        };
+        // the table takes a while to load, so the game can start without it
+        let worker = options.expected_values_path.map(Worker::spawn);
+        Self {
+            game,
+            selected: [false; 5],
+            cursor: 0,
+            mode: Mode::Normal,
+            input: String::new(),
+            status: Status::Info(String::from(
+                "1-5: toggle dice, r: reroll, ↑/↓: move, enter: score, h: hint, :: command, ?: help, q: quit",
+            )),
+            hints: options.hints,
+            show_help: false,
+            rng,
+            seed,
+            history,
+            quit: false,
+            worker,
+            loaded: false,
+            analyses: HashMap::new(),
+            requested: HashSet::new(),
+            pending_hint: None,
+            pending_coach: Vec::new(),
+            coach_threshold: options.coach_threshold,
+        }
+    }
+
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            self.poll_worker();
+            if (self.hints || self.coach_threshold.is_some()) && !self.game.ended() {
+                // analyzed ahead, so that hints and coaching are ready when needed
+                self.request_analysis(self.game);
+            }
+            terminal.draw(|frame| ui::draw(frame, &self))?;
+            if event::poll(Duration::from_millis(100))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn hint_text(&self) -> Option<String> {
+        if !self.hints || self.game.ended() {
+            return None;
+        }
+        match self.analyses.get(&self.game) {
+            Some(analysis) => Some(analysis.text()),
+            None if self.worker.is_some() && !self.loaded => {
+                Some(String::from("loading expected values…"))
+            }
+            None if self.worker.is_some() => Some(String::from("analyzing…")),
+            None => None,
+        }
+    }
+
+    fn poll_worker(&mut self) {
+        loop {
+            let event = match &self.worker {
+                Some(worker) => worker.try_recv(),
+                None => return,
+            };
+            match event {
+                Ok(WorkerEvent::Loaded) => {
+                    self.loaded = true;
+                }
+                Ok(WorkerEvent::LoadFailed(error)) => {
+                    self.status = Status::Error(error.to_string());
+                    self.worker = None;
+                }
+                Ok(WorkerEvent::Analyzed(analysis)) => {
+                    self.requested.remove(&analysis.game);
+                    self.analyses.insert(analysis.game, analysis);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.worker = None;
+                }
+            }
+        }
+        self.show_pending();
+    }
+
+    fn request_analysis(&mut self, game: Game) {
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        if !self.analyses.contains_key(&game) && self.requested.insert(game) {
+            worker.request(game);
+        }
+    }
+
+    // hints and coach warnings waiting for the worker
+    fn show_pending(&mut self) {
+        if let Some(game) = self.pending_hint
+            && let Some(analysis) = self.analyses.get(&game)
+        {
+            if game == self.game {
+                self.status = Status::Info(format!("hint: {}", analysis.text()));
+            }
+            self.pending_hint = None;
+        }
+
+        let Some(threshold) = self.coach_threshold else {
+            return;
+        };
+        let mut pending = Vec::new();
+        for (game, choice) in std::mem::take(&mut self.pending_coach) {
+            let Some(analysis) = self.analyses.get(&game) else {
+                pending.push((game, choice));
+                continue;
+            };
+            // the final score is more important than advice on the last move
+            if let Some(warning) = coach_warning(analysis, choice, threshold)
+                && !self.game.ended()
+            {
+                self.status = Status::Warning(warning);
+            }
+        }
+        self.pending_coach = pending;
+    }
+
+    fn show_hint(&mut self) {
+        if self.game.ended() {
+            self.status = Status::Error(String::from("game ended"));
+            return;
+        }
+        if self.worker.is_none() {
+            self.status = Status::Error(String::from(
+                "hints need an expected values table (--expected-values)",
+            ));
+            return;
+        }
+        if !self.loaded {
+            self.status = Status::Error(String::from("expected values are still loading"));
+            return;
+        }
+        match self.analyses.get(&self.game) {
+            Some(analysis) => {
+                self.status = Status::Info(format!("hint: {}", analysis.text()));
+            }
+            None => {
+                self.request_analysis(self.game);
+                self.pending_hint = Some(self.game);
+                self.status = Status::Info(String::from("hint: analyzing…"));
+            }
+        }
+    }
+
+    fn coach(&mut self, game: Game, choice: Choice) {
+        if self.coach_threshold.is_none() || !self.loaded {
+            return;
+        }
+        self.request_analysis(game);
+        self.pending_coach.push((game, choice));
+        self.show_pending();
+    }
+
+    pub fn cursor_combo(&self) -> Combo {
+        Combo::iter().nth(self.cursor).expect("cursor out of range")
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Command => self.handle_command_key(key),
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(char @ '1'..='5') => {
+                let index = char as usize - '1' as usize;
+                self.selected[index] = !self.selected[index];
+                self.status = Status::None;
+            }
+            KeyCode::Char('r') => {
+                self.reroll_selected();
+            }
+            KeyCode::Esc => {
+                self.selected = [false; 5];
+                self.status = Status::None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.cursor = (self.cursor + 14) % 15;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = (self.cursor + 1) % 15;
+            }
+            KeyCode::Enter => {
+                self.select_combo(self.cursor_combo());
+            }
+            KeyCode::Char('h') => {
+                self.show_hint();
+            }
+            KeyCode::Char('?') => {
+                self.show_help = true;
+            }
+            KeyCode::Char(':') => {
+                self.mode = Mode::Command;
+                self.input.clear();
+            }
+            KeyCode::Char('n') if self.game.ended() => {
+                self.new_game();
+            }
+            KeyCode::Char('q') => {
+                self.quit = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_command_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.input);
+                self.mode = Mode::Normal;
+                self.execute(&input);
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Backspace if self.input.pop().is_none() => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char(char) => {
+                self.input.push(char);
+            }
+            _ => {}
+        }
+    }
+
+    fn execute(&mut self, input: &str) {
+        let command = match input.parse() {
+            Ok(command) => command,
+            Err(ParseCommandError::Empty) => return,
+            Err(error) => {
+                self.status = Status::Error(error.to_string());
+                return;
+            }
+        };
+        match command {
+            Command::Score(combo) => self.select_combo(combo),
+            Command::Reroll(dice) => self.reroll(&dice),
+            Command::Keep(dice) => self.keep(&dice),
+            Command::Hint => self.show_hint(),
+            Command::Help => self.show_help = true,
+            Command::ShowScore => self.show_score(),
+            Command::New => self.new_game(),
+            Command::Save(path) => self.save(&path),
+            Command::Load(path) => self.load(&path),
+            Command::Quit => self.quit = true,
+        }
+    }
+
+    fn show_score(&mut self) {
+        self.status = Status::Info(format!(
+            "score {}, upper section {}/63, round {}",
+            self.game.score(),
+            ui::upper_section_total(&self.game),
+            (self.game.round() + 1).min(15),
+        ));
+    }
+
+    fn save(&mut self, path: &Path) {
+        let rng = self.seed.map(|seed| RngState {
+            seed,
+            word_pos: self.rng.get_word_pos(),
+        });
+        let saved = SavedGame::new(self.game, rng, self.history.clone());
+        self.status = match saved.save(path) {
+            Ok(()) => Status::Info(format!("saved to {}", path.display())),
+            Err(error) => Status::Error(error.to_string()),
+        };
+    }
+
+    fn load(&mut self, path: &Path) {
+        match SavedGame::load(path) {
+            Ok((saved, game)) => {
+                self.game = game;
+                self.history = saved.history;
+                self.forget_analyses();
+                if let Some(state) = saved.rng {
+                    self.rng = restore_rng(state);
+                    self.seed = Some(state.seed);
+                }
+                self.selected = [false; 5];
+                self.status = Status::Info(format!("loaded {}", path.display()));
+            }
+            Err(error) => {
+                self.status = Status::Error(error.to_string());
+            }
+        }
+    }
+
+    fn record(&mut self, game: Game, choice: Choice) {
+        self.history.push(HistoryEntry::new(game, choice));
+    }
+
+    fn new_game(&mut self) {
+        self.game = Game::new_random(&mut self.rng);
+        self.history.clear();
+        self.forget_analyses();
+        self.selected = [false; 5];
+        self.cursor = 0;
+        self.status = Status::Info(String::from("new game"));
+    }
+
+    // analyses of a previous game are not needed again
+    fn forget_analyses(&mut self) {
+        self.analyses.clear();
+        self.pending_hint = None;
+        self.pending_coach.clear();
+    }
+
+    fn reroll_selected(&mut self) {
+        let dice: Vec<_> = self
+            .game
+            .dice()
+            .iter()
+            .zip(self.selected)
+            .filter_map(|(&die, selected)| selected.then_some(die))
+            .collect();
+        if dice.is_empty() {
+            self.status = Status::Error(String::from("select dice to reroll with keys 1-5"));
+            return;
+        }
+        self.reroll(&dice);
+    }
+
+    fn keep(&mut self, dice: &[Die]) {
+        match reroll_for_keep(&*self.game.dice(), dice) {
+            Ok(reroll_dice) => self.reroll(&reroll_dice),
+            Err(error) => self.status = Status::Error(error.to_string()),
+        }
+    }
+
+    fn reroll(&mut self, dice: &[Die]) {
+        let game = self.game;
+        match self.game.reroll(dice, &mut self.rng) {
+            Ok(()) => {
+                let choice = Choice::reroll(dice).expect("invalid number of dice");
+                self.record(game, choice);
+                self.selected = [false; 5];
+                self.status = Status::None;
+                self.coach(game, choice);
+            }
+            Err(error) => {
+                self.status = Status::Error(error.to_string());
+            }
+        }
+    }
+
+    fn select_combo(&mut self, combo: Combo) {
+        let game = self.game;
+        let points = combo.points(self.game.dice());
+        match self.game.select_combo(combo, &mut self.rng) {
+            Ok(()) => {
+                self.record(game, Choice::SelectCombo(combo));
+                self.selected = [false; 5];
+                self.status = if self.game.ended() {
+                    Status::Info(format!(
+                        "game over, final score {} (n: new game, q: quit)",
+                        self.game.score(),
+                    ))
+                } else {
+                    Status::Info(format!("scored {points} in {combo}"))
+                };
+                self.coach(game, Choice::SelectCombo(combo));
+            }
+            Err(error) => {
+                self.status = Status::Error(error.to_string());
+            }
+        }
+    }
+}
+
+fn coach_warning(analysis: &Analysis, choice: Choice, threshold: f64) -> Option<String> {
+    let loss = analysis.value - analysis.choice_value(choice)?;
+    (loss > threshold).then(|| {
+        format!(
+            "coach: {} loses {:.2} expected points, best was {}",
+            choice_text(choice),
+            loss,
+            analysis.text(),
+        )
+    })
+}
+
+pub fn restore_rng(state: RngState) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(state.seed);
+    rng.set_word_pos(state.word_pos);
+    rng
+}
@@ -0,0 +1,226 @@
+use std::{path::PathBuf, str::FromStr};
+
+use yatzy::{Combo, Die};
+
+const COMMANDS: [&str; 10] = [
+    "help", "hint", "keep", "load", "new", "quit", "reroll", "save", "score", "exit",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Score(Combo),
+    Reroll(Vec<Die>),
+    Keep(Vec<Die>),
+    Hint,
+    Help,
+    ShowScore,
+    New,
+    Save(PathBuf),
+    Load(PathBuf),
+    Quit,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseCommandError {
+    #[error("empty command")]
+    Empty,
+    #[error("unknown command `{input}`{}", did_you_mean(.suggestion))]
+    UnknownCommand {
+        input: String,
+        suggestion: Option<String>,
+    },
+    #[error("unknown combo `{input}`{}", did_you_mean(.suggestion))]
+    UnknownCombo {
+        input: String,
+        suggestion: Option<String>,
+    },
+    #[error("invalid die `{0}`, dice are 1-6")]
+    InvalidDie(String),
+    #[error("too many dice, there are only 5")]
+    TooManyDice,
+    #[error("`{0}` needs dice, e.g. `{0} 5 5`")]
+    MissingDice(&'static str),
+    #[error("`{0}` needs a file name")]
+    MissingPath(&'static str),
+    #[error("`{0}` takes no arguments")]
+    UnexpectedArgument(&'static str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum KeepError {
+    #[error("no {0} to keep")]
+    NotInHand(Die),
+    #[error("nothing to reroll")]
+    NothingToReroll,
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean `{suggestion}`?"),
+        None => String::new(),
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseCommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(ParseCommandError::Empty);
+        }
+        let (word, rest) = match input.split_once(char::is_whitespace) {
+            Some((word, rest)) => (word, rest.trim()),
+            None => (input, ""),
+        };
+
+        match word.to_lowercase().as_str() {
+            "score" | "s" if rest.is_empty() => Ok(Self::ShowScore),
+            "score" | "s" => match rest.parse() {
+                Ok(combo) => Ok(Self::Score(combo)),
+                Err(_) => Err(ParseCommandError::UnknownCombo {
+                    input: String::from(rest),
+                    suggestion: suggest_combo(rest).map(|combo| format!("score {combo}")),
+                }),
+            },
+            "reroll" | "r" => {
+                let dice = parse_dice(rest)?;
+                if dice.is_empty() {
+                    return Err(ParseCommandError::MissingDice("reroll"));
+                }
+                Ok(Self::Reroll(dice))
+            }
+            "keep" | "k" => Ok(Self::Keep(parse_dice(rest)?)),
+            "save" if rest.is_empty() => Err(ParseCommandError::MissingPath("save")),
+            "save" => Ok(Self::Save(PathBuf::from(rest))),
+            "load" if rest.is_empty() => Err(ParseCommandError::MissingPath("load")),
+            "load" => Ok(Self::Load(PathBuf::from(rest))),
+            "hint" => no_arguments("hint", rest, Self::Hint),
+            "help" | "?" => no_arguments("help", rest, Self::Help),
+            "new" => no_arguments("new", rest, Self::New),
+            "quit" | "q" | "exit" => no_arguments("quit", rest, Self::Quit),
+            _ => match input.parse() {
+                Ok(combo) => Ok(Self::Score(combo)),
+                Err(_) => Err(ParseCommandError::UnknownCommand {
+                    input: String::from(input),
+                    suggestion: suggest(word, rest, input),
+                }),
+            },
+        }
+    }
+}
+
+pub fn reroll_for_keep(hand: &[Die], keep: &[Die]) -> Result<Vec<Die>, KeepError> {
+    let mut reroll_dice = hand.to_vec();
+    for die in keep {
+        match reroll_dice.iter().position(|x| x == die) {
+            Some(index) => {
+                reroll_dice.remove(index);
+            }
+            None => {
+                return Err(KeepError::NotInHand(*die));
+            }
+        }
+    }
+    if reroll_dice.is_empty() {
+        return Err(KeepError::NothingToReroll);
+    }
+    Ok(reroll_dice)
+}
+
+fn no_arguments(
+    command: &'static str,
+    rest: &str,
+    value: Command,
+) -> Result<Command, ParseCommandError> {
+    if rest.is_empty() {
+        Ok(value)
+    } else {
+        Err(ParseCommandError::UnexpectedArgument(command))
+    }
+}
+
+fn parse_dice(input: &str) -> Result<Vec<Die>, ParseCommandError> {
+    let mut dice = Vec::with_capacity(5);
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        for char in token.chars() {
+            let die = match char {
+                '1'..='6' => char.to_digit(10).unwrap() as Die,
+                _ => return Err(ParseCommandError::InvalidDie(String::from(token))),
+            };
+            if dice.len() >= 5 {
+                return Err(ParseCommandError::TooManyDice);
+            }
+            dice.push(die);
+        }
+    }
+    Ok(dice)
+}
+
+fn suggest(word: &str, rest: &str, input: &str) -> Option<String> {
+    let command = closest(word, COMMANDS.iter().map(|&command| String::from(command))).map(
+        |(command, distance)| {
+            if rest.is_empty() {
+                (command, distance)
+            } else {
+                (format!("{command} {rest}"), distance)
+            }
+        },
+    );
+    let combo = closest(input, Combo::iter().map(|combo| combo.to_string()));
+    match (command, combo) {
+        (Some((command, d1)), Some((combo, d2))) => Some(if d1 <= d2 { command } else { combo }),
+        (command, combo) => command.or(combo).map(|(suggestion, _)| suggestion),
+    }
+}
+
+fn suggest_combo(input: &str) -> Option<String> {
+    closest(input, Combo::iter().map(|combo| combo.to_string())).map(|(combo, _)| combo)
+}
+
+fn closest(input: &str, candidates: impl Iterator<Item = String>) -> Option<(String, usize)> {
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).clamp(1, 3);
+    candidates
+        .map(|candidate| {
+            let distance = edit_distance(&input, &candidate);
+            (candidate, distance)
+        })
+        .filter(|&(_, distance)| distance <= max_distance)
+        .min_by_key(|&(_, distance)| distance)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+pub const HELP: [(&str, &str); 11] = [
+    ("<combo>, score <combo>", "score the dice in a combo"),
+    (
+        "reroll <dice>, r <dice>",
+        "reroll the given dice, e.g. `reroll 1 3`",
+    ),
+    (
+        "keep <dice>, k <dice>",
+        "keep the given dice and reroll the rest",
+    ),
+    ("score, s", "show the current score"),
+    ("hint", "show the best move"),
+    ("new", "start a new game"),
+    ("save <file>", "save the game"),
+    ("load <file>", "load a saved game"),
+    ("help, ?", "show this help"),
+    ("quit, q", "quit"),
+    ("aliases", "1s-6s, 1p, 2p, 3k, 4k, ss, ls, fh, c, y"),
+];
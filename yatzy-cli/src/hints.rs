@@ -0,0 +1,169 @@
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+};
+
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use num_traits::ToPrimitive as _;
+use rustc_hash::FxBuildHasher;
+use yatzy::{Die, Game};
+use yatzy_solver::{
+    Choice, GameState, evaluate_choices,
+    table::{self, TableError},
+};
+
+type Cache = papaya::HashMap<
+    (Game, Option<Choice>),
+    (Option<HashSet<Choice, FxBuildHasher>>, f64),
+    FxBuildHasher,
+>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("failed to read `{0}`: {1}")]
+    Read(String, io::Error),
+    #[error("failed to parse `{0}`: {1}")]
+    Parse(String, TableError),
+}
+
+#[derive(Debug)]
+pub struct Advisor {
+    expected_values: papaya::HashMap<GameState, f64, FxBuildHasher>,
+}
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub game: Game,
+    pub choices: Vec<Choice>,
+    pub value: f64,
+    values: Vec<(Choice, f64)>,
+}
+
+#[derive(Debug)]
+pub enum WorkerEvent {
+    Loaded,
+    LoadFailed(LoadError),
+    Analyzed(Analysis),
+}
+
+// loads the table and then analyzes positions on a thread of its own, so that
+// the interface stays responsive while the solver runs
+#[derive(Debug)]
+pub struct Worker {
+    requests: Sender<Game>,
+    events: Receiver<WorkerEvent>,
+}
+
+impl Worker {
+    pub fn spawn(path: PathBuf) -> Self {
+        let (requests, request_receiver) = mpsc::channel::<Game>();
+        let (event_sender, events) = mpsc::channel();
+        std::thread::spawn(move || {
+            let advisor = match Advisor::load(&path) {
+                Ok(advisor) => advisor,
+                Err(error) => {
+                    _ = event_sender.send(WorkerEvent::LoadFailed(error));
+                    return;
+                }
+            };
+            if event_sender.send(WorkerEvent::Loaded).is_err() {
+                return;
+            }
+            while let Ok(game) = request_receiver.recv() {
+                let analysis = advisor.analyze(game);
+                if event_sender.send(WorkerEvent::Analyzed(analysis)).is_err() {
+                    return;
+                }
+            }
+        });
+        Self { requests, events }
+    }
+
+    pub fn request(&self, game: Game) {
+        _ = self.requests.send(game);
+    }
+
+    pub fn try_recv(&self) -> Result<WorkerEvent, TryRecvError> {
+        self.events.try_recv()
+    }
+}
+
+impl Advisor {
+    pub fn load(path: &Path) -> Result<Self, LoadError> {
+        let bytes = std::fs::read(path)
+            .map_err(|error| LoadError::Read(path.display().to_string(), error))?;
+        let map: std::collections::HashMap<GameState, Ratio<BigUint>> = table::decode(&bytes)
+            .map_err(|error| LoadError::Parse(path.display().to_string(), error))?;
+
+        let expected_values = papaya::HashMap::with_capacity_and_hasher(map.len(), FxBuildHasher);
+        {
+            let expected_values = expected_values.pin();
+            for (state, value) in map {
+                expected_values.insert(state, value.to_f64().unwrap());
+            }
+        }
+        Ok(Self { expected_values })
+    }
+
+    pub fn analyze(&self, game: Game) -> Analysis {
+        let cache: Cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        // every choice is valued, so that the coach can look up any move later
+        let values = evaluate_choices(game, &self.expected_values, &cache);
+        let value = values
+            .iter()
+            .map(|&(_, value)| value)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let mut choices: Vec<_> = values
+            .iter()
+            .filter_map(|&(choice, choice_value)| (choice_value == value).then_some(choice))
+            .collect();
+        choices.sort_by_key(|choice| choice_text(*choice));
+        Analysis {
+            game,
+            choices,
+            value,
+            values,
+        }
+    }
+}
+
+impl Analysis {
+    pub fn choice_value(&self, choice: Choice) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|&&(legal_choice, _)| legal_choice == choice)
+            .map(|&(_, value)| value)
+    }
+
+    pub fn text(&self) -> String {
+        let choices: Vec<_> = self
+            .choices
+            .iter()
+            .map(|&choice| choice_text(choice))
+            .collect();
+        format!(
+            "{} (expected final score {:.2})",
+            choices.join(" or "),
+            self.value,
+        )
+    }
+}
+
+fn reroll_text(dice: &[Die]) -> String {
+    let dice: Vec<_> = dice.iter().map(|die| die.to_string()).collect();
+    format!("reroll {}", dice.join(" "))
+}
+
+pub fn choice_text(choice: Choice) -> String {
+    match choice {
+        Choice::SelectCombo(combo) => format!("score {combo}"),
+        Choice::Reroll1(dice) => reroll_text(&dice),
+        Choice::Reroll2(dice) => reroll_text(&dice),
+        Choice::Reroll3(dice) => reroll_text(&dice),
+        Choice::Reroll4(dice) => reroll_text(&dice),
+        Choice::Reroll5(_) => String::from("reroll all dice"),
+    }
+}
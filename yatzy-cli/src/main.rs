@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{
+    app::{App, Options},
+    save::SavedGame,
+};
+
+mod app;
+mod command;
+mod hints;
+mod save;
+mod script;
+mod ui;
+
+#[derive(Clone, Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long)]
+    expected_values: Option<PathBuf>,
+    #[arg(long)]
+    hints: bool,
+    #[arg(long)]
+    coach: bool,
+    #[arg(long, default_value_t = 1.0)]
+    coach_threshold: f64,
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long)]
+    resume: Option<PathBuf>,
+    #[arg(long, value_name = "FILE")]
+    script: Option<PathBuf>,
+    #[arg(long, requires = "script", conflicts_with = "seed")]
+    dice: Option<String>,
+}
+
+fn main() {
+    let args = Args::parse();
+    if (args.hints || args.coach) && args.expected_values.is_none() {
+        eprintln!("error: --hints and --coach need --expected-values");
+        std::process::exit(2);
+    }
+    let resume = match args.resume {
+        Some(path) => match SavedGame::load(&path) {
+            Ok(resume) => Some(resume),
+            Err(error) => {
+                eprintln!("error: {error}");
+                std::process::exit(2);
+            }
+        },
+        None => None,
+    };
+
+    if let Some(script) = args.script {
+        let dice = match args.dice.as_deref().map(script::parse_dice_sequence) {
+            Some(Ok(dice)) => Some(dice),
+            Some(Err(error)) => {
+                eprintln!("error: {error}");
+                std::process::exit(2);
+            }
+            None => None,
+        };
+        let options = script::Options {
+            script,
+            seed: args.seed,
+            dice,
+            expected_values_path: args.expected_values,
+            resume,
+        };
+        match script::run(options) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(error) => {
+                eprintln!("error: {error}");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let options = Options {
+        expected_values_path: args.expected_values,
+        hints: args.hints,
+        coach_threshold: args.coach.then_some(args.coach_threshold),
+        seed: args.seed,
+        resume,
+    };
+
+    let terminal = ratatui::init();
+    let result = App::new(options).run(terminal);
+    ratatui::restore();
+    if let Err(error) = result {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,144 @@
+use std::{io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use yatzy::{Die, Game, GameOptions, NewGameError};
+use yatzy_solver::Choice;
+
+use crate::hints::choice_text;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedGame {
+    pub dice: [Die; 5],
+    pub rerolls_left: u8,
+    pub scores: Scores,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng: Option<RngState>,
+    #[serde(default)]
+    pub history: Vec<HistoryEntry>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scores {
+    pub ones: Option<u8>,
+    pub twos: Option<u8>,
+    pub threes: Option<u8>,
+    pub fours: Option<u8>,
+    pub fives: Option<u8>,
+    pub sixes: Option<u8>,
+    pub one_pair: Option<u8>,
+    pub two_pairs: Option<u8>,
+    pub three_of_a_kind: Option<u8>,
+    pub four_of_a_kind: Option<u8>,
+    pub small_straight: Option<u8>,
+    pub large_straight: Option<u8>,
+    pub full_house: Option<u8>,
+    pub chance: Option<u8>,
+    pub yatzy: Option<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RngState {
+    pub seed: u64,
+    pub word_pos: u128,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HistoryEntry {
+    pub round: u8,
+    pub dice: [Die; 5],
+    pub action: String,
+}
+
+impl HistoryEntry {
+    // the game as it was before the choice was made
+    pub fn new(game: Game, choice: Choice) -> Self {
+        Self {
+            round: game.round() + 1,
+            dice: *game.dice(),
+            action: choice_text(choice),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SaveFileError {
+    #[error("failed to read `{0}`: {1}")]
+    Read(String, io::Error),
+    #[error("failed to write `{0}`: {1}")]
+    Write(String, io::Error),
+    #[error("failed to parse `{0}`: {1}")]
+    Parse(String, serde_json::Error),
+    #[error("invalid saved game: {0}")]
+    InvalidGame(#[from] NewGameError),
+}
+
+impl SavedGame {
+    pub fn new(game: Game, rng: Option<RngState>, history: Vec<HistoryEntry>) -> Self {
+        let options = GameOptions::from(game);
+        Self {
+            dice: options.dice,
+            rerolls_left: options.rerolls_left,
+            scores: Scores {
+                ones: options.ones,
+                twos: options.twos,
+                threes: options.threes,
+                fours: options.fours,
+                fives: options.fives,
+                sixes: options.sixes,
+                one_pair: options.one_pair,
+                two_pairs: options.two_pairs,
+                three_of_a_kind: options.three_of_a_kind,
+                four_of_a_kind: options.four_of_a_kind,
+                small_straight: options.small_straight,
+                large_straight: options.large_straight,
+                full_house: options.full_house,
+                chance: options.chance,
+                yatzy: options.yatzy,
+            },
+            rng,
+            history,
+        }
+    }
+
+    pub fn game(&self) -> Result<Game, NewGameError> {
+        Game::new(GameOptions {
+            dice: self.dice,
+            rerolls_left: self.rerolls_left,
+            ones: self.scores.ones,
+            twos: self.scores.twos,
+            threes: self.scores.threes,
+            fours: self.scores.fours,
+            fives: self.scores.fives,
+            sixes: self.scores.sixes,
+            one_pair: self.scores.one_pair,
+            two_pairs: self.scores.two_pairs,
+            three_of_a_kind: self.scores.three_of_a_kind,
+            four_of_a_kind: self.scores.four_of_a_kind,
+            small_straight: self.scores.small_straight,
+            large_straight: self.scores.large_straight,
+            full_house: self.scores.full_house,
+            chance: self.scores.chance,
+            yatzy: self.scores.yatzy,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<(Self, Game), SaveFileError> {
+        let string = std::fs::read_to_string(path)
+            .map_err(|error| SaveFileError::Read(path.display().to_string(), error))?;
+        let saved: Self = serde_json::from_str(&string)
+            .map_err(|error| SaveFileError::Parse(path.display().to_string(), error))?;
+        let game = saved.game()?;
+        Ok((saved, game))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SaveFileError> {
+        let mut string = serde_json::to_string_pretty(self).expect("failed to serialize game");
+        string.push('\n');
+        std::fs::write(path, string)
+            .map_err(|error| SaveFileError::Write(path.display().to_string(), error))
+    }
+}
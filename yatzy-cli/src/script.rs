@@ -0,0 +1,459 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use rand::SeedableRng as _;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use yatzy::{Combo, Die, Game, RerollError, SelectComboError};
+use yatzy_solver::Choice;
+
+use crate::{
+    app::restore_rng,
+    command::{Command, HELP, KeepError, ParseCommandError, reroll_for_keep},
+    hints::{Advisor, LoadError, choice_text},
+    save::{HistoryEntry, RngState, SaveFileError, SavedGame, Scores},
+    ui::upper_section_total,
+};
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub script: PathBuf,
+    pub seed: Option<u64>,
+    pub dice: Option<VecDeque<Die>>,
+    pub expected_values_path: Option<PathBuf>,
+    pub resume: Option<(SavedGame, Game)>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScriptError {
+    #[error("failed to read `{0}`: {1}")]
+    Read(String, io::Error),
+    #[error(transparent)]
+    LoadExpectedValues(#[from] LoadError),
+    #[error("dice sequence exhausted")]
+    SequenceExhausted,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidDiceSequence {
+    #[error("invalid die `{0}` in dice sequence, dice are 1-6")]
+    InvalidDie(char),
+}
+
+#[derive(Debug, thiserror::Error)]
+enum StepError {
+    #[error(transparent)]
+    Parse(#[from] ParseCommandError),
+    #[error(transparent)]
+    Keep(#[from] KeepError),
+    #[error(transparent)]
+    Reroll(#[from] RerollError),
+    #[error(transparent)]
+    SelectCombo(#[from] SelectComboError),
+    #[error(transparent)]
+    SaveFile(#[from] SaveFileError),
+    #[error("dice sequence exhausted")]
+    SequenceExhausted,
+    #[error("hints need an expected values table (--expected-values)")]
+    NoExpectedValues,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Start {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        line: Option<usize>,
+        dice: [Die; 5],
+        rerolls_left: u8,
+        round: u8,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seed: Option<u64>,
+    },
+    Reroll {
+        line: usize,
+        rerolled: &'a [Die],
+        dice: [Die; 5],
+        rerolls_left: u8,
+    },
+    Score {
+        line: usize,
+        combo: String,
+        points: u8,
+        total: u16,
+        dice: [Die; 5],
+        rerolls_left: u8,
+    },
+    Scores {
+        line: usize,
+        scores: Scores,
+        upper_section: u8,
+        bonus: bool,
+        total: u16,
+    },
+    Hint {
+        line: usize,
+        choices: Vec<String>,
+        expected_score: f64,
+    },
+    Help {
+        line: usize,
+        commands: &'a [(&'a str, &'a str)],
+    },
+    Saved {
+        line: usize,
+        path: String,
+    },
+    Loaded {
+        line: usize,
+        path: String,
+        dice: [Die; 5],
+        rerolls_left: u8,
+        round: u8,
+    },
+    Error {
+        line: usize,
+        input: &'a str,
+        message: String,
+    },
+    GameOver {
+        score: u16,
+    },
+    Quit {
+        line: usize,
+    },
+}
+
+#[derive(Debug)]
+enum Roller {
+    Random {
+        rng: Box<ChaCha8Rng>,
+        seed: Option<u64>,
+    },
+    Sequence(VecDeque<Die>),
+}
+
+pub fn parse_dice_sequence(input: &str) -> Result<VecDeque<Die>, InvalidDiceSequence> {
+    let mut dice = VecDeque::new();
+    for char in input.chars() {
+        match char {
+            '1'..='6' => dice.push_back(char.to_digit(10).unwrap() as Die),
+            _ if char.is_whitespace() || char == ',' => {}
+            _ => return Err(InvalidDiceSequence::InvalidDie(char)),
+        }
+    }
+    Ok(dice)
+}
+
+fn emit(event: &Event) {
+    println!(
+        "{}",
+        serde_json::to_string(event).expect("failed to serialize event")
+    );
+}
+
+fn replace_all_dice(game: &mut Game, dice: &[Die]) {
+    let old = *game.dice();
+    game.replace_dice(&old, dice)
+        .expect("current dice are always in hand");
+}
+
+impl Roller {
+    fn take(&mut self, n: usize) -> Option<Vec<Die>> {
+        match self {
+            Self::Random { .. } => None,
+            Self::Sequence(sequence) => {
+                if sequence.len() < n {
+                    return None;
+                }
+                Some(sequence.drain(..n).collect())
+            }
+        }
+    }
+
+    fn new_game(&mut self) -> Result<Game, StepError> {
+        match self {
+            Self::Random { rng, .. } => Ok(Game::new_random(rng)),
+            Self::Sequence(_) => {
+                let dice = self.take(5).ok_or(StepError::SequenceExhausted)?;
+                let mut game = Game::new_random(&mut ChaCha8Rng::seed_from_u64(0));
+                replace_all_dice(&mut game, &dice);
+                Ok(game)
+            }
+        }
+    }
+
+    fn reroll(&mut self, game: &mut Game, dice: &[Die]) -> Result<(), StepError> {
+        match self {
+            Self::Random { rng, .. } => Ok(game.reroll(dice, rng)?),
+            Self::Sequence(_) => {
+                if game.ended() {
+                    return Err(RerollError::GameEnded.into());
+                }
+                if game.rerolls_left() == 0 {
+                    return Err(RerollError::NoRerollsLeft.into());
+                }
+                let mut next = *game;
+                if next.replace_dice(dice, dice).is_err() {
+                    return Err(RerollError::InvalidDice.into());
+                }
+                let new_dice = self.take(dice.len()).ok_or(StepError::SequenceExhausted)?;
+                next.replace_dice(dice, &new_dice)
+                    .expect("rerolled dice were checked");
+                next.set_rerolls(game.rerolls_left() - 1);
+                *game = next;
+                Ok(())
+            }
+        }
+    }
+
+    fn select_combo(&mut self, game: &mut Game, combo: Combo) -> Result<(), StepError> {
+        match self {
+            Self::Random { rng, .. } => Ok(game.select_combo(combo, rng)?),
+            Self::Sequence(_) => {
+                let mut next = *game;
+                next.select_combo(combo, &mut ChaCha8Rng::seed_from_u64(0))?;
+                if !next.ended() {
+                    let dice = self.take(5).ok_or(StepError::SequenceExhausted)?;
+                    replace_all_dice(&mut next, &dice);
+                }
+                *game = next;
+                Ok(())
+            }
+        }
+    }
+
+    fn rng_state(&self) -> Option<RngState> {
+        match self {
+            Self::Random {
+                rng,
+                seed: Some(seed),
+            } => Some(RngState {
+                seed: *seed,
+                word_pos: rng.get_word_pos(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        match self {
+            Self::Random { seed, .. } => *seed,
+            Self::Sequence(_) => None,
+        }
+    }
+}
+
+fn open(path: &Path) -> Result<Box<dyn BufRead>, ScriptError> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file =
+        File::open(path).map_err(|error| ScriptError::Read(path.display().to_string(), error))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+fn start_event(game: &Game, line: Option<usize>, seed: Option<u64>) -> Event<'static> {
+    Event::Start {
+        line,
+        dice: *game.dice(),
+        rerolls_left: game.rerolls_left(),
+        round: game.round() + 1,
+        seed,
+    }
+}
+
+pub fn run(options: Options) -> Result<bool, ScriptError> {
+    let reader = open(&options.script)?;
+    let advisor = match &options.expected_values_path {
+        Some(path) => Some(Advisor::load(path)?),
+        None => None,
+    };
+
+    let mut roller = match options.dice {
+        Some(sequence) => Roller::Sequence(sequence),
+        None => Roller::Random {
+            rng: Box::new(match options.seed {
+                Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+                None => ChaCha8Rng::from_os_rng(),
+            }),
+            seed: options.seed,
+        },
+    };
+    let (mut game, mut history) = match options.resume {
+        Some((saved, game)) => {
+            if let (Some(state), Roller::Random { rng, seed }) = (saved.rng, &mut roller) {
+                **rng = restore_rng(state);
+                *seed = Some(state.seed);
+            }
+            (game, saved.history)
+        }
+        None => match roller.new_game() {
+            Ok(game) => (game, Vec::new()),
+            Err(_) => return Err(ScriptError::SequenceExhausted),
+        },
+    };
+    emit(&start_event(&game, None, roller.seed()));
+
+    let mut success = true;
+    for (index, input) in reader.lines().enumerate() {
+        let line = index + 1;
+        let input = input
+            .map_err(|error| ScriptError::Read(options.script.display().to_string(), error))?;
+        let trimmed = input.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match step(
+            &mut game,
+            &mut history,
+            &mut roller,
+            advisor.as_ref(),
+            line,
+            trimmed,
+        ) {
+            Ok(true) => {}
+            Ok(false) => {
+                emit(&Event::Quit { line });
+                break;
+            }
+            Err(error) => {
+                success = false;
+                emit(&Event::Error {
+                    line,
+                    input: trimmed,
+                    message: error.to_string(),
+                });
+                if let StepError::SequenceExhausted = error {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(success)
+}
+
+fn step(
+    game: &mut Game,
+    history: &mut Vec<HistoryEntry>,
+    roller: &mut Roller,
+    advisor: Option<&Advisor>,
+    line: usize,
+    input: &str,
+) -> Result<bool, StepError> {
+    match input.parse()? {
+        Command::Score(combo) => {
+            let points = combo.points(game.dice());
+            let before = *game;
+            roller.select_combo(game, combo)?;
+            history.push(HistoryEntry::new(before, Choice::SelectCombo(combo)));
+            emit(&Event::Score {
+                line,
+                combo: combo.to_string(),
+                points,
+                total: game.score(),
+                dice: *game.dice(),
+                rerolls_left: game.rerolls_left(),
+            });
+            if game.ended() {
+                emit(&Event::GameOver {
+                    score: game.score(),
+                });
+            }
+        }
+        Command::Reroll(dice) => {
+            let before = *game;
+            roller.reroll(game, &dice)?;
+            history.push(HistoryEntry::new(
+                before,
+                Choice::reroll(&dice).expect("invalid number of dice"),
+            ));
+            emit(&Event::Reroll {
+                line,
+                rerolled: &dice,
+                dice: *game.dice(),
+                rerolls_left: game.rerolls_left(),
+            });
+        }
+        Command::Keep(dice) => {
+            let dice = reroll_for_keep(&*game.dice(), &dice)?;
+            let before = *game;
+            roller.reroll(game, &dice)?;
+            history.push(HistoryEntry::new(
+                before,
+                Choice::reroll(&dice).expect("invalid number of dice"),
+            ));
+            emit(&Event::Reroll {
+                line,
+                rerolled: &dice,
+                dice: *game.dice(),
+                rerolls_left: game.rerolls_left(),
+            });
+        }
+        Command::Hint => {
+            let advisor = advisor.ok_or(StepError::NoExpectedValues)?;
+            if game.ended() {
+                return Err(SelectComboError::GameEnded.into());
+            }
+            let analysis = advisor.analyze(*game);
+            emit(&Event::Hint {
+                line,
+                choices: analysis
+                    .choices
+                    .iter()
+                    .map(|&choice| choice_text(choice))
+                    .collect(),
+                expected_score: analysis.value,
+            });
+        }
+        Command::Help => {
+            emit(&Event::Help {
+                line,
+                commands: &HELP,
+            });
+        }
+        Command::ShowScore => {
+            emit(&Event::Scores {
+                line,
+                scores: SavedGame::new(*game, None, history.clone()).scores,
+                upper_section: upper_section_total(game),
+                bonus: game.has_bonus(),
+                total: game.score(),
+            });
+        }
+        Command::New => {
+            *game = roller.new_game()?;
+            history.clear();
+            emit(&start_event(game, Some(line), roller.seed()));
+        }
+        Command::Save(path) => {
+            SavedGame::new(*game, roller.rng_state(), history.clone()).save(&path)?;
+            emit(&Event::Saved {
+                line,
+                path: path.display().to_string(),
+            });
+        }
+        Command::Load(path) => {
+            let (saved, loaded) = SavedGame::load(&path)?;
+            if let (Some(state), Roller::Random { rng, seed }) = (saved.rng, &mut *roller) {
+                **rng = restore_rng(state);
+                *seed = Some(state.seed);
+            }
+            *game = loaded;
+            *history = saved.history;
+            emit(&Event::Loaded {
+                line,
+                path: path.display().to_string(),
+                dice: *game.dice(),
+                rerolls_left: game.rerolls_left(),
+                round: game.round() + 1,
+            });
+        }
+        Command::Quit => return Ok(false),
+    }
+    Ok(true)
+}
@@ -0,0 +1,185 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize as _},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Row, Table},
+};
+use yatzy::{Combo, Game};
+
+use crate::{
+    app::{App, Mode, Status},
+    command::HELP,
+};
+
+const BONUS_THRESHOLD: u8 = 63;
+
+fn combo_label(combo: Combo) -> String {
+    let name = combo.to_string();
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [
+        dice_area,
+        hint_area,
+        scoresheet_area,
+        status_area,
+        input_area,
+    ] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(dice(app), dice_area);
+    if let Some(hint) = app.hint_text() {
+        frame.render_widget(Paragraph::new(format!("hint: {hint}")).cyan(), hint_area);
+    }
+    frame.render_widget(scoresheet(app), scoresheet_area);
+    frame.render_widget(status(app), status_area);
+    frame.render_widget(input(app), input_area);
+
+    if app.show_help {
+        let area = help_area(frame.area());
+        frame.render_widget(Clear, area);
+        frame.render_widget(help(), area);
+    }
+}
+
+fn help_area(area: Rect) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(HELP.len() as u16 + 2)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(72)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+fn help() -> Table<'static> {
+    let rows = HELP.iter().map(|&(command, description)| {
+        Row::new([Line::from(command).bold(), Line::from(description)])
+    });
+    Table::new(rows, [Constraint::Length(24), Constraint::Min(0)]).block(
+        Block::bordered()
+            .title(" Commands ")
+            .title_bottom(Line::from(" press any key ").right_aligned()),
+    )
+}
+
+fn dice(app: &App) -> Paragraph<'static> {
+    let mut labels = Vec::with_capacity(5);
+    let mut faces = Vec::with_capacity(5);
+    for (index, (&die, selected)) in app.game.dice().iter().zip(app.selected).enumerate() {
+        labels.push(Span::raw(format!("  {}   ", index + 1)).dark_gray());
+        let face = Span::raw(format!("[ {die} ]"));
+        faces.push(if selected {
+            face.black().on_yellow()
+        } else {
+            face.bold()
+        });
+        faces.push(Span::raw(" "));
+    }
+
+    let rerolls_left = match app.game.rerolls_left() {
+        1 => String::from("1 reroll left"),
+        n => format!("{n} rerolls left"),
+    };
+    Paragraph::new(vec![Line::from(labels), Line::from(faces)]).block(
+        Block::bordered()
+            .title(format!(" Round {} ", (app.game.round() + 1).min(15)))
+            .title_bottom(Line::from(format!(" {rerolls_left} ")).right_aligned()),
+    )
+}
+
+pub fn upper_section_total(game: &Game) -> u8 {
+    Combo::iter()
+        .take(6)
+        .map(|combo| game.combo(combo).unwrap_or(0))
+        .sum()
+}
+
+fn bonus_row(game: &Game) -> Row<'static> {
+    let total = upper_section_total(game);
+    if total >= BONUS_THRESHOLD {
+        return Row::new(["Bonus", "50"]).green().bold();
+    }
+
+    let needed = BONUS_THRESHOLD - total;
+    let reachable: u8 = Combo::iter()
+        .take(6)
+        .zip(1..)
+        .filter(|&(combo, _)| game.combo(combo).is_none())
+        .map(|(_, face)| 5 * face)
+        .sum();
+    if reachable < needed {
+        Row::new([String::from("Bonus"), String::from("missed")]).red()
+    } else {
+        Row::new([String::from("Bonus"), format!("{needed} to go")]).yellow()
+    }
+}
+
+fn scoresheet(app: &App) -> Table<'static> {
+    let game = &app.game;
+    let mut rows = Vec::with_capacity(18);
+
+    for (index, combo) in Combo::iter().enumerate() {
+        let score = match game.combo(combo) {
+            Some(points) => Span::raw(points.to_string()),
+            None if game.ended() => Span::raw(""),
+            None => Span::raw(format!("({})", combo.points(game.dice())))
+                .dark_gray()
+                .italic(),
+        };
+        let mut row = Row::new([Line::from(combo_label(combo)), Line::from(score)]);
+        if index == app.cursor && !game.ended() {
+            row = row.add_modifier(Modifier::REVERSED);
+        }
+        rows.push(row);
+
+        if combo == Combo::Sixes {
+            let total = upper_section_total(game);
+            let style = if total >= BONUS_THRESHOLD {
+                Style::new().fg(Color::Green)
+            } else {
+                Style::new().fg(Color::Yellow)
+            };
+            rows.push(
+                Row::new([
+                    String::from("Upper section"),
+                    format!("{total}/{BONUS_THRESHOLD}"),
+                ])
+                .style(style),
+            );
+            rows.push(bonus_row(game));
+        }
+    }
+    rows.push(Row::new([String::from("Total"), game.score().to_string()]).bold());
+
+    Table::new(rows, [Constraint::Length(18), Constraint::Length(10)])
+        .block(Block::bordered().title(" Scoresheet "))
+}
+
+fn status(app: &App) -> Paragraph<'static> {
+    match &app.status {
+        Status::None => Paragraph::new(""),
+        Status::Info(message) => Paragraph::new(message.clone()),
+        Status::Warning(message) => Paragraph::new(message.clone()).yellow(),
+        Status::Error(message) => Paragraph::new(format!("error: {message}")).red(),
+    }
+}
+
+fn input(app: &App) -> Paragraph<'static> {
+    match app.mode {
+        Mode::Normal => Paragraph::new(""),
+        Mode::Command => Paragraph::new(format!(":{}", app.input)),
+    }
+}
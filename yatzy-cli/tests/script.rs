@@ -0,0 +1,76 @@
+use std::{
+    io::Write as _,
+    process::{Command, Output, Stdio},
+};
+
+fn run_script(script: &str, dice: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_yatzy"))
+        .args(["--script", "-", "--dice", dice])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start yatzy");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn fixed_dice_give_the_same_events() {
+    let output = run_script(
+        "# comments and blank lines are skipped\n\
+         \n\
+         reroll 1 2\n\
+         keep 6 6\n\
+         score sixes\n\
+         score\n\
+         bogus\n\
+         quit\n",
+        "12666 34 551 11234",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        concat!(
+            r#"{"event":"start","dice":[1,2,6,6,6],"rerolls_left":2,"round":1}"#,
+            "\n",
+            r#"{"event":"reroll","line":3,"rerolled":[1,2],"dice":[3,4,6,6,6],"rerolls_left":1}"#,
+            "\n",
+            r#"{"event":"reroll","line":4,"rerolled":[3,4,6],"dice":[1,5,5,6,6],"rerolls_left":0}"#,
+            "\n",
+            r#"{"event":"score","line":5,"combo":"sixes","points":12,"total":12,"dice":[1,1,2,3,4],"rerolls_left":2}"#,
+            "\n",
+            r#"{"event":"scores","line":6,"scores":{"ones":null,"twos":null,"threes":null,"fours":null,"fives":null,"sixes":12,"one_pair":null,"two_pairs":null,"three_of_a_kind":null,"four_of_a_kind":null,"small_straight":null,"large_straight":null,"full_house":null,"chance":null,"yatzy":null},"upper_section":12,"bonus":false,"total":12}"#,
+            "\n",
+            r#"{"event":"error","line":7,"input":"bogus","message":"unknown command `bogus`"}"#,
+            "\n",
+            r#"{"event":"quit","line":8}"#,
+            "\n",
+        ),
+    );
+}
+
+#[test]
+fn saved_games_keep_the_move_history() {
+    let path = std::env::temp_dir().join(format!("yatzy-script-test-{}.json", std::process::id()));
+    let output = run_script(
+        &format!("reroll 1 2\nscore sixes\nsave {}\n", path.display()),
+        "12666 34 11234",
+    );
+    assert!(output.status.success());
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    _ = std::fs::remove_file(&path);
+    assert_eq!(
+        saved["history"],
+        serde_json::json!([
+            { "round": 1, "dice": [1, 2, 6, 6, 6], "action": "reroll 1 2" },
+            { "round": 1, "dice": [3, 4, 6, 6, 6], "action": "score sixes" },
+        ]),
+    );
+}
@@ -0,0 +1,391 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead as _, BufReader, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt8Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use num_traits::Zero as _;
+use parquet::{
+    arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder},
+    errors::ParquetError,
+};
+use rustc_hash::FxBuildHasher;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use yatzy_compute_expected_values::{
+    FieldState, GameState, Value,
+    table::{self, TableError},
+};
+
+const PARQUET_BATCH_SIZE: usize = 65_536;
+
+const FIELD_COLUMNS: [&str; 15] = [
+    "ones",
+    "twos",
+    "threes",
+    "fours",
+    "fives",
+    "sixes",
+    "one_pair",
+    "two_pairs",
+    "three_of_a_kind",
+    "four_of_a_kind",
+    "small_straight",
+    "large_straight",
+    "full_house",
+    "chance",
+    "yatzy",
+];
+
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct ExportArgs {
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+    input: PathBuf,
+    output: PathBuf,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct ImportArgs {
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+    input: PathBuf,
+    output: PathBuf,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("`{0}`: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("`{0}`: {1}")]
+    Postcard(PathBuf, postcard::Error),
+    #[error("`{0}`: {1}")]
+    Table(PathBuf, TableError),
+    #[error("{0}")]
+    Csv(#[from] csv::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Arrow(#[from] ArrowError),
+    #[error("{0}")]
+    Parquet(#[from] ParquetError),
+    #[error("missing or mistyped column `{0}`")]
+    InvalidColumn(&'static str),
+    #[error("row {0}: {1}")]
+    InvalidRow(usize, &'static str),
+}
+
+// one row per game state; a field flag is true when the field is filled, and
+// the exact value is given as a numerator and denominator in decimal since
+// rational values do not fit in any fixed-width integer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Row {
+    ones: bool,
+    twos: bool,
+    threes: bool,
+    fours: bool,
+    fives: bool,
+    sixes: bool,
+    one_pair: bool,
+    two_pairs: bool,
+    three_of_a_kind: bool,
+    four_of_a_kind: bool,
+    small_straight: bool,
+    large_straight: bool,
+    full_house: bool,
+    chance: bool,
+    yatzy: bool,
+    numbers_total: u8,
+    value: f64,
+    numerator: String,
+    denominator: String,
+}
+
+impl Row {
+    fn new<V: Value>(state: GameState, value: &V) -> Self {
+        let ratio = value.to_ratio();
+        Self {
+            ones: state.ones == FieldState::Filled,
+            twos: state.twos == FieldState::Filled,
+            threes: state.threes == FieldState::Filled,
+            fours: state.fours == FieldState::Filled,
+            fives: state.fives == FieldState::Filled,
+            sixes: state.sixes == FieldState::Filled,
+            one_pair: state.one_pair == FieldState::Filled,
+            two_pairs: state.two_pairs == FieldState::Filled,
+            three_of_a_kind: state.three_of_a_kind == FieldState::Filled,
+            four_of_a_kind: state.four_of_a_kind == FieldState::Filled,
+            small_straight: state.small_straight == FieldState::Filled,
+            large_straight: state.large_straight == FieldState::Filled,
+            full_house: state.full_house == FieldState::Filled,
+            chance: state.chance == FieldState::Filled,
+            yatzy: state.yatzy == FieldState::Filled,
+            numbers_total: state.numbers_total,
+            value: value.to_f64(),
+            numerator: ratio.numer().to_string(),
+            denominator: ratio.denom().to_string(),
+        }
+    }
+
+    fn flags(&self) -> [bool; 15] {
+        [
+            self.ones,
+            self.twos,
+            self.threes,
+            self.fours,
+            self.fives,
+            self.sixes,
+            self.one_pair,
+            self.two_pairs,
+            self.three_of_a_kind,
+            self.four_of_a_kind,
+            self.small_straight,
+            self.large_straight,
+            self.full_house,
+            self.chance,
+            self.yatzy,
+        ]
+    }
+
+    // the float column is only informational; the exact columns are imported
+    fn into_entry<V: Value>(self, row: usize) -> Result<(GameState, V), ExportError> {
+        let field = |filled| {
+            if filled {
+                FieldState::Filled
+            } else {
+                FieldState::Empty
+            }
+        };
+        if self.numbers_total > 63 {
+            return Err(ExportError::InvalidRow(row, "numbers_total exceeds 63"));
+        }
+        let state = GameState {
+            numbers_total: self.numbers_total,
+            ones: field(self.ones),
+            twos: field(self.twos),
+            threes: field(self.threes),
+            fours: field(self.fours),
+            fives: field(self.fives),
+            sixes: field(self.sixes),
+            one_pair: field(self.one_pair),
+            two_pairs: field(self.two_pairs),
+            three_of_a_kind: field(self.three_of_a_kind),
+            four_of_a_kind: field(self.four_of_a_kind),
+            small_straight: field(self.small_straight),
+            large_straight: field(self.large_straight),
+            full_house: field(self.full_house),
+            chance: field(self.chance),
+            yatzy: field(self.yatzy),
+        };
+        let Ok(numerator) = self.numerator.parse::<BigUint>() else {
+            return Err(ExportError::InvalidRow(row, "invalid numerator"));
+        };
+        let Ok(denominator) = self.denominator.parse::<BigUint>() else {
+            return Err(ExportError::InvalidRow(row, "invalid denominator"));
+        };
+        if denominator.is_zero() {
+            return Err(ExportError::InvalidRow(row, "zero denominator"));
+        }
+        Ok((state, V::from_ratio(&Ratio::new(numerator, denominator))))
+    }
+}
+
+fn schema() -> Schema {
+    let mut fields: Vec<Field> = FIELD_COLUMNS
+        .iter()
+        .map(|name| Field::new(*name, DataType::Boolean, false))
+        .collect();
+    fields.push(Field::new("numbers_total", DataType::UInt8, false));
+    fields.push(Field::new("value", DataType::Float64, false));
+    fields.push(Field::new("numerator", DataType::Utf8, false));
+    fields.push(Field::new("denominator", DataType::Utf8, false));
+    Schema::new(fields)
+}
+
+fn create(path: &Path) -> Result<BufWriter<File>, ExportError> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|error| ExportError::Io(path.to_owned(), error))
+}
+
+fn open(path: &Path) -> Result<File, ExportError> {
+    File::open(path).map_err(|error| ExportError::Io(path.to_owned(), error))
+}
+
+fn write_parquet(path: &Path, rows: &[Row]) -> Result<(), ExportError> {
+    let schema = Arc::new(schema());
+    let mut writer = ArrowWriter::try_new(create(path)?, schema.clone(), None)?;
+    for rows in rows.chunks(PARQUET_BATCH_SIZE) {
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(19);
+        for i in 0..FIELD_COLUMNS.len() {
+            let column: BooleanArray = rows.iter().map(|row| Some(row.flags()[i])).collect();
+            columns.push(Arc::new(column));
+        }
+        columns.push(Arc::new(UInt8Array::from_iter_values(
+            rows.iter().map(|row| row.numbers_total),
+        )));
+        columns.push(Arc::new(Float64Array::from_iter_values(
+            rows.iter().map(|row| row.value),
+        )));
+        columns.push(Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| &row.numerator),
+        )));
+        columns.push(Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| &row.denominator),
+        )));
+        writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+fn column<'a, T: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &'static str,
+) -> Result<&'a T, ExportError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<T>())
+        .ok_or(ExportError::InvalidColumn(name))
+}
+
+fn read_parquet(path: &Path) -> Result<Vec<Row>, ExportError> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(open(path)?)?.build()?;
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch?;
+        let mut flags = Vec::with_capacity(FIELD_COLUMNS.len());
+        for name in FIELD_COLUMNS {
+            flags.push(column::<BooleanArray>(&batch, name)?);
+        }
+        let numbers_total = column::<UInt8Array>(&batch, "numbers_total")?;
+        let value = column::<Float64Array>(&batch, "value")?;
+        let numerator = column::<StringArray>(&batch, "numerator")?;
+        let denominator = column::<StringArray>(&batch, "denominator")?;
+        for i in 0..batch.num_rows() {
+            rows.push(Row {
+                ones: flags[0].value(i),
+                twos: flags[1].value(i),
+                threes: flags[2].value(i),
+                fours: flags[3].value(i),
+                fives: flags[4].value(i),
+                sixes: flags[5].value(i),
+                one_pair: flags[6].value(i),
+                two_pairs: flags[7].value(i),
+                three_of_a_kind: flags[8].value(i),
+                four_of_a_kind: flags[9].value(i),
+                small_straight: flags[10].value(i),
+                large_straight: flags[11].value(i),
+                full_house: flags[12].value(i),
+                chance: flags[13].value(i),
+                yatzy: flags[14].value(i),
+                numbers_total: numbers_total.value(i),
+                value: value.value(i),
+                numerator: String::from(numerator.value(i)),
+                denominator: String::from(denominator.value(i)),
+            });
+        }
+    }
+    Ok(rows)
+}
+
+pub fn export<V: Value + DeserializeOwned>(args: &ExportArgs) -> Result<(), ExportError> {
+    let bytes =
+        std::fs::read(&args.input).map_err(|error| ExportError::Io(args.input.clone(), error))?;
+    let expected_values: HashMap<GameState, V, FxBuildHasher> =
+        table::decode(&bytes).map_err(|error| ExportError::Table(args.input.clone(), error))?;
+
+    let mut rows: Vec<Row> = expected_values
+        .iter()
+        .map(|(&state, value)| Row::new(state, value))
+        .collect();
+    rows.sort_by_key(|row| (row.flags(), row.numbers_total));
+
+    match args.format {
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(create(&args.output)?);
+            for row in &rows {
+                writer.serialize(row)?;
+            }
+            writer
+                .flush()
+                .map_err(|error| ExportError::Io(args.output.clone(), error))?;
+        }
+        Format::JsonLines => {
+            let mut writer = create(&args.output)?;
+            for row in &rows {
+                serde_json::to_writer(&mut writer, row)?;
+                writeln!(writer).map_err(|error| ExportError::Io(args.output.clone(), error))?;
+            }
+            writer
+                .flush()
+                .map_err(|error| ExportError::Io(args.output.clone(), error))?;
+        }
+        Format::Parquet => {
+            write_parquet(&args.output, &rows)?;
+        }
+    }
+    eprintln!(
+        "exported {} states to {}",
+        rows.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+pub fn import<V: Value + Serialize>(args: &ImportArgs) -> Result<(), ExportError> {
+    let rows: Vec<Row> = match args.format {
+        Format::Csv => {
+            let mut reader = csv::Reader::from_reader(open(&args.input)?);
+            reader.deserialize().collect::<Result<_, _>>()?
+        }
+        Format::JsonLines => {
+            let mut rows = Vec::new();
+            for line in BufReader::new(open(&args.input)?).lines() {
+                let line = line.map_err(|error| ExportError::Io(args.input.clone(), error))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                rows.push(serde_json::from_str(&line)?);
+            }
+            rows
+        }
+        Format::Parquet => read_parquet(&args.input)?,
+    };
+
+    let mut expected_values: HashMap<GameState, V, FxBuildHasher> =
+        HashMap::with_capacity_and_hasher(rows.len(), FxBuildHasher);
+    for (i, row) in rows.into_iter().enumerate() {
+        let (state, value) = row.into_entry(i + 1)?;
+        if expected_values.insert(state, value).is_some() {
+            return Err(ExportError::InvalidRow(i + 1, "duplicate game state"));
+        }
+    }
+
+    let bytes = table::encode(&expected_values)
+        .map_err(|error| ExportError::Postcard(args.output.clone(), error))?;
+    std::fs::write(&args.output, bytes)
+        .map_err(|error| ExportError::Io(args.output.clone(), error))?;
+    eprintln!(
+        "imported {} states to {}",
+        expected_values.len(),
+        args.output.display(),
+    );
+    Ok(())
+}
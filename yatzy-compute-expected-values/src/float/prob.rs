@@ -1,477 +0,0 @@
-use yatzy::Die;
-
-pub const ROLL_1_PROB: [([Die; 1], f64); 6] = [
-    ([1], 0.16666666666666666),
-    ([2], 0.16666666666666666),
-    ([3], 0.16666666666666666),
-    ([4], 0.16666666666666666),
-    ([5], 0.16666666666666666),
-    ([6], 0.16666666666666666),
-];
-
-pub const ROLL_2_PROB: [([Die; 2], f64); 21] = [
-    ([1, 1], 0.027777777777777776),
-    ([1, 2], 0.05555555555555555),
-    ([1, 3], 0.05555555555555555),
-    ([1, 4], 0.05555555555555555),
-    ([1, 5], 0.05555555555555555),
-    ([1, 6], 0.05555555555555555),
-    ([2, 2], 0.027777777777777776),
-    ([2, 3], 0.05555555555555555),
-    ([2, 4], 0.05555555555555555),
-    ([2, 5], 0.05555555555555555),
-    ([2, 6], 0.05555555555555555),
-    ([3, 3], 0.027777777777777776),
-    ([3, 4], 0.05555555555555555),
-    ([3, 5], 0.05555555555555555),
-    ([3, 6], 0.05555555555555555),
-    ([4, 4], 0.027777777777777776),
-    ([4, 5], 0.05555555555555555),
-    ([4, 6], 0.05555555555555555),
-    ([5, 5], 0.027777777777777776),
-    ([5, 6], 0.05555555555555555),
-    ([6, 6], 0.027777777777777776),
-];
-
-pub const ROLL_3_PROB: [([Die; 3], f64); 56] = [
-    ([1, 1, 1], 0.004629629629629629),
-    ([1, 1, 2], 0.013888888888888888),
-    ([1, 1, 3], 0.013888888888888888),
-    ([1, 1, 4], 0.013888888888888888),
-    ([1, 1, 5], 0.013888888888888888),
-    ([1, 1, 6], 0.013888888888888888),
-    ([1, 2, 2], 0.013888888888888888),
-    ([1, 2, 3], 0.027777777777777776),
-    ([1, 2, 4], 0.027777777777777776),
-    ([1, 2, 5], 0.027777777777777776),
-    ([1, 2, 6], 0.027777777777777776),
-    ([1, 3, 3], 0.013888888888888888),
-    ([1, 3, 4], 0.027777777777777776),
-    ([1, 3, 5], 0.027777777777777776),
-    ([1, 3, 6], 0.027777777777777776),
-    ([1, 4, 4], 0.013888888888888888),
-    ([1, 4, 5], 0.027777777777777776),
-    ([1, 4, 6], 0.027777777777777776),
-    ([1, 5, 5], 0.013888888888888888),
-    ([1, 5, 6], 0.027777777777777776),
-    ([1, 6, 6], 0.013888888888888888),
-    ([2, 2, 2], 0.004629629629629629),
-    ([2, 2, 3], 0.013888888888888888),
-    ([2, 2, 4], 0.013888888888888888),
-    ([2, 2, 5], 0.013888888888888888),
-    ([2, 2, 6], 0.013888888888888888),
-    ([2, 3, 3], 0.013888888888888888),
-    ([2, 3, 4], 0.027777777777777776),
-    ([2, 3, 5], 0.027777777777777776),
-    ([2, 3, 6], 0.027777777777777776),
-    ([2, 4, 4], 0.013888888888888888),
-    ([2, 4, 5], 0.027777777777777776),
-    ([2, 4, 6], 0.027777777777777776),
-    ([2, 5, 5], 0.013888888888888888),
-    ([2, 5, 6], 0.027777777777777776),
-    ([2, 6, 6], 0.013888888888888888),
-    ([3, 3, 3], 0.004629629629629629),
-    ([3, 3, 4], 0.013888888888888888),
-    ([3, 3, 5], 0.013888888888888888),
-    ([3, 3, 6], 0.013888888888888888),
-    ([3, 4, 4], 0.013888888888888888),
-    ([3, 4, 5], 0.027777777777777776),
-    ([3, 4, 6], 0.027777777777777776),
-    ([3, 5, 5], 0.013888888888888888),
-    ([3, 5, 6], 0.027777777777777776),
-    ([3, 6, 6], 0.013888888888888888),
-    ([4, 4, 4], 0.004629629629629629),
-    ([4, 4, 5], 0.013888888888888888),
-    ([4, 4, 6], 0.013888888888888888),
-    ([4, 5, 5], 0.013888888888888888),
-    ([4, 5, 6], 0.027777777777777776),
-    ([4, 6, 6], 0.013888888888888888),
-    ([5, 5, 5], 0.004629629629629629),
-    ([5, 5, 6], 0.013888888888888888),
-    ([5, 6, 6], 0.013888888888888888),
-    ([6, 6, 6], 0.004629629629629629),
-];
-
-pub const ROLL_4_PROB: [([Die; 4], f64); 126] = [
-    ([1, 1, 1, 1], 0.0007716049382716049),
-    ([1, 1, 1, 2], 0.0030864197530864196),
-    ([1, 1, 1, 3], 0.0030864197530864196),
-    ([1, 1, 1, 4], 0.0030864197530864196),
-    ([1, 1, 1, 5], 0.0030864197530864196),
-    ([1, 1, 1, 6], 0.0030864197530864196),
-    ([1, 1, 2, 2], 0.004629629629629629),
-    ([1, 1, 2, 3], 0.009259259259259259),
-    ([1, 1, 2, 4], 0.009259259259259259),
-    ([1, 1, 2, 5], 0.009259259259259259),
-    ([1, 1, 2, 6], 0.009259259259259259),
-    ([1, 1, 3, 3], 0.004629629629629629),
-    ([1, 1, 3, 4], 0.009259259259259259),
-    ([1, 1, 3, 5], 0.009259259259259259),
-    ([1, 1, 3, 6], 0.009259259259259259),
-    ([1, 1, 4, 4], 0.004629629629629629),
-    ([1, 1, 4, 5], 0.009259259259259259),
-    ([1, 1, 4, 6], 0.009259259259259259),
-    ([1, 1, 5, 5], 0.004629629629629629),
-    ([1, 1, 5, 6], 0.009259259259259259),
-    ([1, 1, 6, 6], 0.004629629629629629),
-    ([1, 2, 2, 2], 0.0030864197530864196),
-    ([1, 2, 2, 3], 0.009259259259259259),
-    ([1, 2, 2, 4], 0.009259259259259259),
-    ([1, 2, 2, 5], 0.009259259259259259),
-    ([1, 2, 2, 6], 0.009259259259259259),
-    ([1, 2, 3, 3], 0.009259259259259259),
-    ([1, 2, 3, 4], 0.018518518518518517),
-    ([1, 2, 3, 5], 0.018518518518518517),
-    ([1, 2, 3, 6], 0.018518518518518517),
-    ([1, 2, 4, 4], 0.009259259259259259),
-    ([1, 2, 4, 5], 0.018518518518518517),
-    ([1, 2, 4, 6], 0.018518518518518517),
-    ([1, 2, 5, 5], 0.009259259259259259),
-    ([1, 2, 5, 6], 0.018518518518518517),
-    ([1, 2, 6, 6], 0.009259259259259259),
-    ([1, 3, 3, 3], 0.0030864197530864196),
-    ([1, 3, 3, 4], 0.009259259259259259),
-    ([1, 3, 3, 5], 0.009259259259259259),
-    ([1, 3, 3, 6], 0.009259259259259259),
-    ([1, 3, 4, 4], 0.009259259259259259),
-    ([1, 3, 4, 5], 0.018518518518518517),
-    ([1, 3, 4, 6], 0.018518518518518517),
-    ([1, 3, 5, 5], 0.009259259259259259),
-    ([1, 3, 5, 6], 0.018518518518518517),
-    ([1, 3, 6, 6], 0.009259259259259259),
-    ([1, 4, 4, 4], 0.0030864197530864196),
-    ([1, 4, 4, 5], 0.009259259259259259),
-    ([1, 4, 4, 6], 0.009259259259259259),
-    ([1, 4, 5, 5], 0.009259259259259259),
-    ([1, 4, 5, 6], 0.018518518518518517),
-    ([1, 4, 6, 6], 0.009259259259259259),
-    ([1, 5, 5, 5], 0.0030864197530864196),
-    ([1, 5, 5, 6], 0.009259259259259259),
-    ([1, 5, 6, 6], 0.009259259259259259),
-    ([1, 6, 6, 6], 0.0030864197530864196),
-    ([2, 2, 2, 2], 0.0007716049382716049),
-    ([2, 2, 2, 3], 0.0030864197530864196),
-    ([2, 2, 2, 4], 0.0030864197530864196),
-    ([2, 2, 2, 5], 0.0030864197530864196),
-    ([2, 2, 2, 6], 0.0030864197530864196),
-    ([2, 2, 3, 3], 0.004629629629629629),
-    ([2, 2, 3, 4], 0.009259259259259259),
-    ([2, 2, 3, 5], 0.009259259259259259),
-    ([2, 2, 3, 6], 0.009259259259259259),
-    ([2, 2, 4, 4], 0.004629629629629629),
-    ([2, 2, 4, 5], 0.009259259259259259),
-    ([2, 2, 4, 6], 0.009259259259259259),
-    ([2, 2, 5, 5], 0.004629629629629629),
-    ([2, 2, 5, 6], 0.009259259259259259),
-    ([2, 2, 6, 6], 0.004629629629629629),
-    ([2, 3, 3, 3], 0.0030864197530864196),
-    ([2, 3, 3, 4], 0.009259259259259259),
-    ([2, 3, 3, 5], 0.009259259259259259),
-    ([2, 3, 3, 6], 0.009259259259259259),
-    ([2, 3, 4, 4], 0.009259259259259259),
-    ([2, 3, 4, 5], 0.018518518518518517),
-    ([2, 3, 4, 6], 0.018518518518518517),
-    ([2, 3, 5, 5], 0.009259259259259259),
-    ([2, 3, 5, 6], 0.018518518518518517),
-    ([2, 3, 6, 6], 0.009259259259259259),
-    ([2, 4, 4, 4], 0.0030864197530864196),
-    ([2, 4, 4, 5], 0.009259259259259259),
-    ([2, 4, 4, 6], 0.009259259259259259),
-    ([2, 4, 5, 5], 0.009259259259259259),
-    ([2, 4, 5, 6], 0.018518518518518517),
-    ([2, 4, 6, 6], 0.009259259259259259),
-    ([2, 5, 5, 5], 0.0030864197530864196),
-    ([2, 5, 5, 6], 0.009259259259259259),
-    ([2, 5, 6, 6], 0.009259259259259259),
-    ([2, 6, 6, 6], 0.0030864197530864196),
-    ([3, 3, 3, 3], 0.0007716049382716049),
-    ([3, 3, 3, 4], 0.0030864197530864196),
-    ([3, 3, 3, 5], 0.0030864197530864196),
-    ([3, 3, 3, 6], 0.0030864197530864196),
-    ([3, 3, 4, 4], 0.004629629629629629),
-    ([3, 3, 4, 5], 0.009259259259259259),
-    ([3, 3, 4, 6], 0.009259259259259259),
-    ([3, 3, 5, 5], 0.004629629629629629),
-    ([3, 3, 5, 6], 0.009259259259259259),
-    ([3, 3, 6, 6], 0.004629629629629629),
-    ([3, 4, 4, 4], 0.0030864197530864196),
-    ([3, 4, 4, 5], 0.009259259259259259),
-    ([3, 4, 4, 6], 0.009259259259259259),
-    ([3, 4, 5, 5], 0.009259259259259259),
-    ([3, 4, 5, 6], 0.018518518518518517),
-    ([3, 4, 6, 6], 0.009259259259259259),
-    ([3, 5, 5, 5], 0.0030864197530864196),
-    ([3, 5, 5, 6], 0.009259259259259259),
-    ([3, 5, 6, 6], 0.009259259259259259),
-    ([3, 6, 6, 6], 0.0030864197530864196),
-    ([4, 4, 4, 4], 0.0007716049382716049),
-    ([4, 4, 4, 5], 0.0030864197530864196),
-    ([4, 4, 4, 6], 0.0030864197530864196),
-    ([4, 4, 5, 5], 0.004629629629629629),
-    ([4, 4, 5, 6], 0.009259259259259259),
-    ([4, 4, 6, 6], 0.004629629629629629),
-    ([4, 5, 5, 5], 0.0030864197530864196),
-    ([4, 5, 5, 6], 0.009259259259259259),
-    ([4, 5, 6, 6], 0.009259259259259259),
-    ([4, 6, 6, 6], 0.0030864197530864196),
-    ([5, 5, 5, 5], 0.0007716049382716049),
-    ([5, 5, 5, 6], 0.0030864197530864196),
-    ([5, 5, 6, 6], 0.004629629629629629),
-    ([5, 6, 6, 6], 0.0030864197530864196),
-    ([6, 6, 6, 6], 0.0007716049382716049),
-];
-
-pub const ROLL_5_PROB: [([Die; 5], f64); 252] = [
-    ([1, 1, 1, 1, 1], 0.0001286008230452675),
-    ([1, 1, 1, 1, 2], 0.0006430041152263374),
-    ([1, 1, 1, 1, 3], 0.0006430041152263374),
-    ([1, 1, 1, 1, 4], 0.0006430041152263374),
-    ([1, 1, 1, 1, 5], 0.0006430041152263374),
-    ([1, 1, 1, 1, 6], 0.0006430041152263374),
-    ([1, 1, 1, 2, 2], 0.001286008230452675),
-    ([1, 1, 1, 2, 3], 0.00257201646090535),
-    ([1, 1, 1, 2, 4], 0.00257201646090535),
-    ([1, 1, 1, 2, 5], 0.00257201646090535),
-    ([1, 1, 1, 2, 6], 0.00257201646090535),
-    ([1, 1, 1, 3, 3], 0.001286008230452675),
-    ([1, 1, 1, 3, 4], 0.00257201646090535),
-    ([1, 1, 1, 3, 5], 0.00257201646090535),
-    ([1, 1, 1, 3, 6], 0.00257201646090535),
-    ([1, 1, 1, 4, 4], 0.001286008230452675),
-    ([1, 1, 1, 4, 5], 0.00257201646090535),
-    ([1, 1, 1, 4, 6], 0.00257201646090535),
-    ([1, 1, 1, 5, 5], 0.001286008230452675),
-    ([1, 1, 1, 5, 6], 0.00257201646090535),
-    ([1, 1, 1, 6, 6], 0.001286008230452675),
-    ([1, 1, 2, 2, 2], 0.001286008230452675),
-    ([1, 1, 2, 2, 3], 0.0038580246913580245),
-    ([1, 1, 2, 2, 4], 0.0038580246913580245),
-    ([1, 1, 2, 2, 5], 0.0038580246913580245),
-    ([1, 1, 2, 2, 6], 0.0038580246913580245),
-    ([1, 1, 2, 3, 3], 0.0038580246913580245),
-    ([1, 1, 2, 3, 4], 0.007716049382716049),
-    ([1, 1, 2, 3, 5], 0.007716049382716049),
-    ([1, 1, 2, 3, 6], 0.007716049382716049),
-    ([1, 1, 2, 4, 4], 0.0038580246913580245),
-    ([1, 1, 2, 4, 5], 0.007716049382716049),
-    ([1, 1, 2, 4, 6], 0.007716049382716049),
-    ([1, 1, 2, 5, 5], 0.0038580246913580245),
-    ([1, 1, 2, 5, 6], 0.007716049382716049),
-    ([1, 1, 2, 6, 6], 0.0038580246913580245),
-    ([1, 1, 3, 3, 3], 0.001286008230452675),
-    ([1, 1, 3, 3, 4], 0.0038580246913580245),
-    ([1, 1, 3, 3, 5], 0.0038580246913580245),
-    ([1, 1, 3, 3, 6], 0.0038580246913580245),
-    ([1, 1, 3, 4, 4], 0.0038580246913580245),
-    ([1, 1, 3, 4, 5], 0.007716049382716049),
-    ([1, 1, 3, 4, 6], 0.007716049382716049),
-    ([1, 1, 3, 5, 5], 0.0038580246913580245),
-    ([1, 1, 3, 5, 6], 0.007716049382716049),
-    ([1, 1, 3, 6, 6], 0.0038580246913580245),
-    ([1, 1, 4, 4, 4], 0.001286008230452675),
-    ([1, 1, 4, 4, 5], 0.0038580246913580245),
-    ([1, 1, 4, 4, 6], 0.0038580246913580245),
-    ([1, 1, 4, 5, 5], 0.0038580246913580245),
-    ([1, 1, 4, 5, 6], 0.007716049382716049),
-    ([1, 1, 4, 6, 6], 0.0038580246913580245),
-    ([1, 1, 5, 5, 5], 0.001286008230452675),
-    ([1, 1, 5, 5, 6], 0.0038580246913580245),
-    ([1, 1, 5, 6, 6], 0.0038580246913580245),
-    ([1, 1, 6, 6, 6], 0.001286008230452675),
-    ([1, 2, 2, 2, 2], 0.0006430041152263374),
-    ([1, 2, 2, 2, 3], 0.00257201646090535),
-    ([1, 2, 2, 2, 4], 0.00257201646090535),
-    ([1, 2, 2, 2, 5], 0.00257201646090535),
-    ([1, 2, 2, 2, 6], 0.00257201646090535),
-    ([1, 2, 2, 3, 3], 0.0038580246913580245),
-    ([1, 2, 2, 3, 4], 0.007716049382716049),
-    ([1, 2, 2, 3, 5], 0.007716049382716049),
-    ([1, 2, 2, 3, 6], 0.007716049382716049),
-    ([1, 2, 2, 4, 4], 0.0038580246913580245),
-    ([1, 2, 2, 4, 5], 0.007716049382716049),
-    ([1, 2, 2, 4, 6], 0.007716049382716049),
-    ([1, 2, 2, 5, 5], 0.0038580246913580245),
-    ([1, 2, 2, 5, 6], 0.007716049382716049),
-    ([1, 2, 2, 6, 6], 0.0038580246913580245),
-    ([1, 2, 3, 3, 3], 0.00257201646090535),
-    ([1, 2, 3, 3, 4], 0.007716049382716049),
-    ([1, 2, 3, 3, 5], 0.007716049382716049),
-    ([1, 2, 3, 3, 6], 0.007716049382716049),
-    ([1, 2, 3, 4, 4], 0.007716049382716049),
-    ([1, 2, 3, 4, 5], 0.015432098765432098),
-    ([1, 2, 3, 4, 6], 0.015432098765432098),
-    ([1, 2, 3, 5, 5], 0.007716049382716049),
-    ([1, 2, 3, 5, 6], 0.015432098765432098),
-    ([1, 2, 3, 6, 6], 0.007716049382716049),
-    ([1, 2, 4, 4, 4], 0.00257201646090535),
-    ([1, 2, 4, 4, 5], 0.007716049382716049),
-    ([1, 2, 4, 4, 6], 0.007716049382716049),
-    ([1, 2, 4, 5, 5], 0.007716049382716049),
-    ([1, 2, 4, 5, 6], 0.015432098765432098),
-    ([1, 2, 4, 6, 6], 0.007716049382716049),
-    ([1, 2, 5, 5, 5], 0.00257201646090535),
-    ([1, 2, 5, 5, 6], 0.007716049382716049),
-    ([1, 2, 5, 6, 6], 0.007716049382716049),
-    ([1, 2, 6, 6, 6], 0.00257201646090535),
-    ([1, 3, 3, 3, 3], 0.0006430041152263374),
-    ([1, 3, 3, 3, 4], 0.00257201646090535),
-    ([1, 3, 3, 3, 5], 0.00257201646090535),
-    ([1, 3, 3, 3, 6], 0.00257201646090535),
-    ([1, 3, 3, 4, 4], 0.0038580246913580245),
-    ([1, 3, 3, 4, 5], 0.007716049382716049),
-    ([1, 3, 3, 4, 6], 0.007716049382716049),
-    ([1, 3, 3, 5, 5], 0.0038580246913580245),
-    ([1, 3, 3, 5, 6], 0.007716049382716049),
-    ([1, 3, 3, 6, 6], 0.0038580246913580245),
-    ([1, 3, 4, 4, 4], 0.00257201646090535),
-    ([1, 3, 4, 4, 5], 0.007716049382716049),
-    ([1, 3, 4, 4, 6], 0.007716049382716049),
-    ([1, 3, 4, 5, 5], 0.007716049382716049),
-    ([1, 3, 4, 5, 6], 0.015432098765432098),
-    ([1, 3, 4, 6, 6], 0.007716049382716049),
-    ([1, 3, 5, 5, 5], 0.00257201646090535),
-    ([1, 3, 5, 5, 6], 0.007716049382716049),
-    ([1, 3, 5, 6, 6], 0.007716049382716049),
-    ([1, 3, 6, 6, 6], 0.00257201646090535),
-    ([1, 4, 4, 4, 4], 0.0006430041152263374),
-    ([1, 4, 4, 4, 5], 0.00257201646090535),
-    ([1, 4, 4, 4, 6], 0.00257201646090535),
-    ([1, 4, 4, 5, 5], 0.0038580246913580245),
-    ([1, 4, 4, 5, 6], 0.007716049382716049),
-    ([1, 4, 4, 6, 6], 0.0038580246913580245),
-    ([1, 4, 5, 5, 5], 0.00257201646090535),
-    ([1, 4, 5, 5, 6], 0.007716049382716049),
-    ([1, 4, 5, 6, 6], 0.007716049382716049),
-    ([1, 4, 6, 6, 6], 0.00257201646090535),
-    ([1, 5, 5, 5, 5], 0.0006430041152263374),
-    ([1, 5, 5, 5, 6], 0.00257201646090535),
-    ([1, 5, 5, 6, 6], 0.0038580246913580245),
-    ([1, 5, 6, 6, 6], 0.00257201646090535),
-    ([1, 6, 6, 6, 6], 0.0006430041152263374),
-    ([2, 2, 2, 2, 2], 0.0001286008230452675),
-    ([2, 2, 2, 2, 3], 0.0006430041152263374),
-    ([2, 2, 2, 2, 4], 0.0006430041152263374),
-    ([2, 2, 2, 2, 5], 0.0006430041152263374),
-    ([2, 2, 2, 2, 6], 0.0006430041152263374),
-    ([2, 2, 2, 3, 3], 0.001286008230452675),
-    ([2, 2, 2, 3, 4], 0.00257201646090535),
-    ([2, 2, 2, 3, 5], 0.00257201646090535),
-    ([2, 2, 2, 3, 6], 0.00257201646090535),
-    ([2, 2, 2, 4, 4], 0.001286008230452675),
-    ([2, 2, 2, 4, 5], 0.00257201646090535),
-    ([2, 2, 2, 4, 6], 0.00257201646090535),
-    ([2, 2, 2, 5, 5], 0.001286008230452675),
-    ([2, 2, 2, 5, 6], 0.00257201646090535),
-    ([2, 2, 2, 6, 6], 0.001286008230452675),
-    ([2, 2, 3, 3, 3], 0.001286008230452675),
-    ([2, 2, 3, 3, 4], 0.0038580246913580245),
-    ([2, 2, 3, 3, 5], 0.0038580246913580245),
-    ([2, 2, 3, 3, 6], 0.0038580246913580245),
-    ([2, 2, 3, 4, 4], 0.0038580246913580245),
-    ([2, 2, 3, 4, 5], 0.007716049382716049),
-    ([2, 2, 3, 4, 6], 0.007716049382716049),
-    ([2, 2, 3, 5, 5], 0.0038580246913580245),
-    ([2, 2, 3, 5, 6], 0.007716049382716049),
-    ([2, 2, 3, 6, 6], 0.0038580246913580245),
-    ([2, 2, 4, 4, 4], 0.001286008230452675),
-    ([2, 2, 4, 4, 5], 0.0038580246913580245),
-    ([2, 2, 4, 4, 6], 0.0038580246913580245),
-    ([2, 2, 4, 5, 5], 0.0038580246913580245),
-    ([2, 2, 4, 5, 6], 0.007716049382716049),
-    ([2, 2, 4, 6, 6], 0.0038580246913580245),
-    ([2, 2, 5, 5, 5], 0.001286008230452675),
-    ([2, 2, 5, 5, 6], 0.0038580246913580245),
-    ([2, 2, 5, 6, 6], 0.0038580246913580245),
-    ([2, 2, 6, 6, 6], 0.001286008230452675),
-    ([2, 3, 3, 3, 3], 0.0006430041152263374),
-    ([2, 3, 3, 3, 4], 0.00257201646090535),
-    ([2, 3, 3, 3, 5], 0.00257201646090535),
-    ([2, 3, 3, 3, 6], 0.00257201646090535),
-    ([2, 3, 3, 4, 4], 0.0038580246913580245),
-    ([2, 3, 3, 4, 5], 0.007716049382716049),
-    ([2, 3, 3, 4, 6], 0.007716049382716049),
-    ([2, 3, 3, 5, 5], 0.0038580246913580245),
-    ([2, 3, 3, 5, 6], 0.007716049382716049),
-    ([2, 3, 3, 6, 6], 0.0038580246913580245),
-    ([2, 3, 4, 4, 4], 0.00257201646090535),
-    ([2, 3, 4, 4, 5], 0.007716049382716049),
-    ([2, 3, 4, 4, 6], 0.007716049382716049),
-    ([2, 3, 4, 5, 5], 0.007716049382716049),
-    ([2, 3, 4, 5, 6], 0.015432098765432098),
-    ([2, 3, 4, 6, 6], 0.007716049382716049),
-    ([2, 3, 5, 5, 5], 0.00257201646090535),
-    ([2, 3, 5, 5, 6], 0.007716049382716049),
-    ([2, 3, 5, 6, 6], 0.007716049382716049),
-    ([2, 3, 6, 6, 6], 0.00257201646090535),
-    ([2, 4, 4, 4, 4], 0.0006430041152263374),
-    ([2, 4, 4, 4, 5], 0.00257201646090535),
-    ([2, 4, 4, 4, 6], 0.00257201646090535),
-    ([2, 4, 4, 5, 5], 0.0038580246913580245),
-    ([2, 4, 4, 5, 6], 0.007716049382716049),
-    ([2, 4, 4, 6, 6], 0.0038580246913580245),
-    ([2, 4, 5, 5, 5], 0.00257201646090535),
-    ([2, 4, 5, 5, 6], 0.007716049382716049),
-    ([2, 4, 5, 6, 6], 0.007716049382716049),
-    ([2, 4, 6, 6, 6], 0.00257201646090535),
-    ([2, 5, 5, 5, 5], 0.0006430041152263374),
-    ([2, 5, 5, 5, 6], 0.00257201646090535),
-    ([2, 5, 5, 6, 6], 0.0038580246913580245),
-    ([2, 5, 6, 6, 6], 0.00257201646090535),
-    ([2, 6, 6, 6, 6], 0.0006430041152263374),
-    ([3, 3, 3, 3, 3], 0.0001286008230452675),
-    ([3, 3, 3, 3, 4], 0.0006430041152263374),
-    ([3, 3, 3, 3, 5], 0.0006430041152263374),
-    ([3, 3, 3, 3, 6], 0.0006430041152263374),
-    ([3, 3, 3, 4, 4], 0.001286008230452675),
-    ([3, 3, 3, 4, 5], 0.00257201646090535),
-    ([3, 3, 3, 4, 6], 0.00257201646090535),
-    ([3, 3, 3, 5, 5], 0.001286008230452675),
-    ([3, 3, 3, 5, 6], 0.00257201646090535),
-    ([3, 3, 3, 6, 6], 0.001286008230452675),
-    ([3, 3, 4, 4, 4], 0.001286008230452675),
-    ([3, 3, 4, 4, 5], 0.0038580246913580245),
-    ([3, 3, 4, 4, 6], 0.0038580246913580245),
-    ([3, 3, 4, 5, 5], 0.0038580246913580245),
-    ([3, 3, 4, 5, 6], 0.007716049382716049),
-    ([3, 3, 4, 6, 6], 0.0038580246913580245),
-    ([3, 3, 5, 5, 5], 0.001286008230452675),
-    ([3, 3, 5, 5, 6], 0.0038580246913580245),
-    ([3, 3, 5, 6, 6], 0.0038580246913580245),
-    ([3, 3, 6, 6, 6], 0.001286008230452675),
-    ([3, 4, 4, 4, 4], 0.0006430041152263374),
-    ([3, 4, 4, 4, 5], 0.00257201646090535),
-    ([3, 4, 4, 4, 6], 0.00257201646090535),
-    ([3, 4, 4, 5, 5], 0.0038580246913580245),
-    ([3, 4, 4, 5, 6], 0.007716049382716049),
-    ([3, 4, 4, 6, 6], 0.0038580246913580245),
-    ([3, 4, 5, 5, 5], 0.00257201646090535),
-    ([3, 4, 5, 5, 6], 0.007716049382716049),
-    ([3, 4, 5, 6, 6], 0.007716049382716049),
-    ([3, 4, 6, 6, 6], 0.00257201646090535),
-    ([3, 5, 5, 5, 5], 0.0006430041152263374),
-    ([3, 5, 5, 5, 6], 0.00257201646090535),
-    ([3, 5, 5, 6, 6], 0.0038580246913580245),
-    ([3, 5, 6, 6, 6], 0.00257201646090535),
-    ([3, 6, 6, 6, 6], 0.0006430041152263374),
-    ([4, 4, 4, 4, 4], 0.0001286008230452675),
-    ([4, 4, 4, 4, 5], 0.0006430041152263374),
-    ([4, 4, 4, 4, 6], 0.0006430041152263374),
-    ([4, 4, 4, 5, 5], 0.001286008230452675),
-    ([4, 4, 4, 5, 6], 0.00257201646090535),
-    ([4, 4, 4, 6, 6], 0.001286008230452675),
-    ([4, 4, 5, 5, 5], 0.001286008230452675),
-    ([4, 4, 5, 5, 6], 0.0038580246913580245),
-    ([4, 4, 5, 6, 6], 0.0038580246913580245),
-    ([4, 4, 6, 6, 6], 0.001286008230452675),
-    ([4, 5, 5, 5, 5], 0.0006430041152263374),
-    ([4, 5, 5, 5, 6], 0.00257201646090535),
-    ([4, 5, 5, 6, 6], 0.0038580246913580245),
-    ([4, 5, 6, 6, 6], 0.00257201646090535),
-    ([4, 6, 6, 6, 6], 0.0006430041152263374),
-    ([5, 5, 5, 5, 5], 0.0001286008230452675),
-    ([5, 5, 5, 5, 6], 0.0006430041152263374),
-    ([5, 5, 5, 6, 6], 0.001286008230452675),
-    ([5, 5, 6, 6, 6], 0.001286008230452675),
-    ([5, 6, 6, 6, 6], 0.0006430041152263374),
-    ([6, 6, 6, 6, 6], 0.0001286008230452675),
-];
@@ -0,0 +1,200 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+    iter::Sum,
+    ops::{AddAssign, Mul},
+};
+
+use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
+use rustc_hash::FxBuildHasher;
+use yatzy::{Combo, Dice, Game, transitions};
+
+use crate::{GameState, Value, game_from_state, state_from_game};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    Position(Game),
+    Retain(u8, usize, GameState),
+    SelectCombo(Combo, Dice, GameState),
+}
+
+fn select_combo_value<V, S1, S2>(
+    game: Game,
+    combo: Combo,
+    expected_values: &HashMap<GameState, V, S1>,
+    cache: &papaya::HashMap<CacheKey, V, S2>,
+) -> V
+where
+    V: Value + AddAssign + Clone,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    let key = CacheKey::SelectCombo(combo, game.dice(), state_from_game(game));
+    if let Some(value) = cache.pin().get(&key) {
+        return value.clone();
+    }
+
+    let mut game = game;
+    let combo_points = combo.points(game.dice());
+    game.set_combo_raw(combo, Some(combo_points));
+    let mut value = V::from_u8(combo_points);
+    if game.ended() {
+        if game.has_bonus() {
+            value += V::from_u8(50);
+        }
+    } else {
+        value += expected_values.get(&state_from_game(game)).unwrap().clone();
+    }
+
+    cache.pin().insert(key, value.clone());
+    value
+}
+
+fn reroll_value<V, S1, S2>(
+    game: Game,
+    keep: usize,
+    expected_values: &HashMap<GameState, V, S1>,
+    cache: &papaya::HashMap<CacheKey, V, S2>,
+) -> V
+where
+    V: Value + AddAssign + Clone + PartialOrd + for<'a> Sum<<&'a V as Mul<V>>::Output>,
+    for<'a> &'a V: Mul<V>,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    // hands that keep the same dice lead to the same outcomes
+    let rerolls_left = game.rerolls_left() - 1;
+    let key = CacheKey::Retain(rerolls_left, keep, state_from_game(game));
+    if let Some(value) = cache.pin().get(&key) {
+        return value.clone();
+    }
+
+    let value: V = V::transition_prob()[keep]
+        .iter()
+        .map(|(dice, prob)| {
+            let mut game = game;
+            game.set_dice_raw(transitions::dice_from_index(*dice));
+            game.set_rerolls(rerolls_left);
+            prob * position_value(game, expected_values, cache)
+        })
+        .sum();
+
+    cache.pin().insert(key, value.clone());
+    value
+}
+
+fn position_value<V, S1, S2>(
+    game: Game,
+    expected_values: &HashMap<GameState, V, S1>,
+    cache: &papaya::HashMap<CacheKey, V, S2>,
+) -> V
+where
+    V: Value + AddAssign + Clone + PartialOrd + for<'a> Sum<<&'a V as Mul<V>>::Output>,
+    for<'a> &'a V: Mul<V>,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    if let Some(value) = cache.pin().get(&CacheKey::Position(game)) {
+        return value.clone();
+    }
+
+    let mut max_expected_value = V::zero();
+    for combo in Combo::iter() {
+        if game.combo(combo).is_none() {
+            let value = select_combo_value(game, combo, expected_values, cache);
+            if value > max_expected_value {
+                max_expected_value = value;
+            }
+        }
+    }
+    if game.rerolls_left() > 0 {
+        for &keep in transitions::keeps(game.dice().index()) {
+            if transitions::keep_len(keep) < 5 {
+                let value = reroll_value(game, keep, expected_values, cache);
+                if value > max_expected_value {
+                    max_expected_value = value;
+                }
+            }
+        }
+    }
+
+    cache
+        .pin()
+        .insert(CacheKey::Position(game), max_expected_value.clone());
+    max_expected_value
+}
+
+pub fn expected_value_2_rerolls<V, S1, S2>(
+    game: Game,
+    expected_values: &HashMap<GameState, V, S1>,
+    cache: &papaya::HashMap<CacheKey, V, S2>,
+) -> V
+where
+    V: Value + AddAssign + Clone + PartialOrd + for<'a> Sum<<&'a V as Mul<V>>::Output>,
+    for<'a> &'a V: Mul<V>,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    assert!(game.rerolls_left() == 2);
+    position_value(game, expected_values, cache)
+}
+
+pub fn state_value<V, S>(state: GameState, expected_values: &HashMap<GameState, V, S>) -> V
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+    S: BuildHasher + Sync,
+{
+    let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+    let keep = transitions::keep_index(&[]).unwrap();
+    V::transition_prob()[keep]
+        .par_iter()
+        .map(|(dice, prob)| {
+            let game = game_from_state(state, transitions::dice_from_index(*dice));
+            prob * expected_value_2_rerolls(game, expected_values, &cache)
+        })
+        .sum()
+}
+
+pub fn compute_expected_values<V, S1, S2>(
+    states: &HashSet<GameState, S1>,
+    expected_values: &HashMap<GameState, V, S2>,
+    count: usize,
+) -> HashMap<GameState, V, FxBuildHasher>
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+    S1: BuildHasher,
+    S2: BuildHasher + Sync,
+{
+    let mut i = 0;
+    states
+        .iter()
+        .filter(|state| !expected_values.contains_key(state))
+        .take(count)
+        .map(|&state| {
+            let value = state_value(state, expected_values);
+            if i % 100 == 0 {
+                eprint!(".");
+            }
+            i += 1;
+            (state, value)
+        })
+        .collect()
+}
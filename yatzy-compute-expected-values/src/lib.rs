@@ -1,11 +1,18 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 use rustc_hash::FxBuildHasher;
 use serde::{Deserialize, Serialize};
 use yatzy::{Combo, Dice, Die, Game};
 
-pub mod float;
-pub mod rational;
+pub mod generate;
+pub mod prob;
+pub mod table;
+mod value;
+
+pub use value::{Fixed, Value};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 enum NumberState {
@@ -44,6 +51,99 @@ pub struct GameState {
     pub yatzy: FieldState,
 }
 
+impl GameState {
+    pub fn initial() -> Self {
+        Self {
+            numbers_total: 0,
+            ones: FieldState::Empty,
+            twos: FieldState::Empty,
+            threes: FieldState::Empty,
+            fours: FieldState::Empty,
+            fives: FieldState::Empty,
+            sixes: FieldState::Empty,
+            one_pair: FieldState::Empty,
+            two_pairs: FieldState::Empty,
+            three_of_a_kind: FieldState::Empty,
+            four_of_a_kind: FieldState::Empty,
+            small_straight: FieldState::Empty,
+            large_straight: FieldState::Empty,
+            full_house: FieldState::Empty,
+            chance: FieldState::Empty,
+            yatzy: FieldState::Empty,
+        }
+    }
+
+    pub fn field(&self, combo: Combo) -> FieldState {
+        match combo {
+            Combo::Ones => self.ones,
+            Combo::Twos => self.twos,
+            Combo::Threes => self.threes,
+            Combo::Fours => self.fours,
+            Combo::Fives => self.fives,
+            Combo::Sixes => self.sixes,
+            Combo::OnePair => self.one_pair,
+            Combo::TwoPairs => self.two_pairs,
+            Combo::ThreeOfAKind => self.three_of_a_kind,
+            Combo::FourOfAKind => self.four_of_a_kind,
+            Combo::SmallStraight => self.small_straight,
+            Combo::LargeStraight => self.large_straight,
+            Combo::FullHouse => self.full_house,
+            Combo::Chance => self.chance,
+            Combo::Yatzy => self.yatzy,
+        }
+    }
+
+    pub fn set_field(&mut self, combo: Combo, field: FieldState) {
+        match combo {
+            Combo::Ones => {
+                self.ones = field;
+            }
+            Combo::Twos => {
+                self.twos = field;
+            }
+            Combo::Threes => {
+                self.threes = field;
+            }
+            Combo::Fours => {
+                self.fours = field;
+            }
+            Combo::Fives => {
+                self.fives = field;
+            }
+            Combo::Sixes => {
+                self.sixes = field;
+            }
+            Combo::OnePair => {
+                self.one_pair = field;
+            }
+            Combo::TwoPairs => {
+                self.two_pairs = field;
+            }
+            Combo::ThreeOfAKind => {
+                self.three_of_a_kind = field;
+            }
+            Combo::FourOfAKind => {
+                self.four_of_a_kind = field;
+            }
+            Combo::SmallStraight => {
+                self.small_straight = field;
+            }
+            Combo::LargeStraight => {
+                self.large_straight = field;
+            }
+            Combo::FullHouse => {
+                self.full_house = field;
+            }
+            Combo::Chance => {
+                self.chance = field;
+            }
+            Combo::Yatzy => {
+                self.yatzy = field;
+            }
+        }
+    }
+}
+
 pub fn game_from_state(state: GameState, dice: Dice) -> Game {
     let mut numbers_filled = false;
     let ones = match state.ones {
@@ -423,3 +523,132 @@ pub enum Choice {
     Reroll4([Die; 4]),
     Reroll5([Die; 5]),
 }
+
+impl Choice {
+    pub fn reroll(dice: &[Die]) -> Option<Self> {
+        let mut dice = dice.to_vec();
+        dice.sort_unstable();
+        match *dice.as_slice() {
+            [d1] => Some(Self::Reroll1([d1])),
+            [d1, d2] => Some(Self::Reroll2([d1, d2])),
+            [d1, d2, d3] => Some(Self::Reroll3([d1, d2, d3])),
+            [d1, d2, d3, d4] => Some(Self::Reroll4([d1, d2, d3, d4])),
+            [d1, d2, d3, d4, d5] => Some(Self::Reroll5([d1, d2, d3, d4, d5])),
+            _ => None,
+        }
+    }
+
+    pub fn rerolled_dice(&self) -> Option<&[Die]> {
+        match self {
+            Self::SelectCombo(_) => None,
+            Self::Reroll1(dice) => Some(dice),
+            Self::Reroll2(dice) => Some(dice),
+            Self::Reroll3(dice) => Some(dice),
+            Self::Reroll4(dice) => Some(dice),
+            Self::Reroll5(dice) => Some(dice),
+        }
+    }
+
+    pub fn keep(&self, hand: &[Die]) -> Option<Keep> {
+        Keep::from_reroll(hand, self.rerolled_dice()?)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Keep {
+    counts: [u8; 6],
+}
+
+impl Keep {
+    pub fn from_counts(counts: [u8; 6]) -> Option<Self> {
+        if counts.iter().map(|&count| u16::from(count)).sum::<u16>() > 5 {
+            return None;
+        }
+        Some(Self { counts })
+    }
+
+    pub fn from_dice(dice: &[Die]) -> Option<Self> {
+        if dice.len() > 5 {
+            return None;
+        }
+        let mut counts = [0; 6];
+        for &die in dice {
+            if !(1..=6).contains(&die) {
+                return None;
+            }
+            counts[usize::from(die - 1)] += 1;
+        }
+        Some(Self { counts })
+    }
+
+    pub fn from_reroll(hand: &[Die], reroll: &[Die]) -> Option<Self> {
+        let hand = Self::from_dice(hand)?;
+        let reroll = Self::from_dice(reroll)?;
+        let mut counts = hand.counts;
+        for (count, &rerolled) in counts.iter_mut().zip(&reroll.counts) {
+            *count = count.checked_sub(rerolled)?;
+        }
+        Some(Self { counts })
+    }
+
+    // every distinct multiset of dice that can be kept from the hand while
+    // still rerolling at least one die, each listed exactly once
+    pub fn all(hand: &[Die]) -> Vec<Self> {
+        let hand = Self::from_dice(hand).expect("invalid hand");
+        let mut keeps = vec![Self::default()];
+        for i in 0..6 {
+            let mut next = Vec::new();
+            for keep in keeps {
+                for count in 0..=hand.counts[i] {
+                    let mut keep = keep;
+                    keep.counts[i] = count;
+                    next.push(keep);
+                }
+            }
+            keeps = next;
+        }
+        keeps.retain(|&keep| keep != hand);
+        keeps
+    }
+
+    pub fn counts(&self) -> [u8; 6] {
+        self.counts
+    }
+
+    pub fn count(&self, die: Die) -> u8 {
+        self.counts[usize::from(die - 1)]
+    }
+
+    pub fn len(&self) -> u8 {
+        self.counts.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn dice(&self) -> Vec<Die> {
+        let mut dice = Vec::with_capacity(5);
+        for (die, &count) in (1..=6).zip(&self.counts) {
+            for _ in 0..count {
+                dice.push(die);
+            }
+        }
+        dice
+    }
+
+    pub fn reroll(&self, hand: &[Die]) -> Option<Vec<Die>> {
+        Self::from_reroll(hand, &self.dice()).map(|reroll| reroll.dice())
+    }
+
+    pub fn reroll_choice(&self, hand: &[Die]) -> Option<Choice> {
+        Choice::reroll(&self.reroll(hand)?)
+    }
+}
+
+impl fmt::Display for Keep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dice: Vec<_> = self.dice().iter().map(|die| die.to_string()).collect();
+        write!(f, "{}", dice.join(","))
+    }
+}
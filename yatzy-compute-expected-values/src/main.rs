@@ -1,101 +1,221 @@
 use std::{
-    collections::{HashMap, HashSet},
-    fs::OpenOptions,
-    hash::BuildHasher,
-    io::Write as _,
+    collections::HashMap,
+    fmt::Display,
+    iter::Sum,
+    ops::{AddAssign, Mul},
+    path::{Path, PathBuf},
 };
 
-use chrono::Utc;
+use clap::Parser;
 use num_bigint::BigUint;
 use num_rational::Ratio;
-use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use rustc_hash::FxBuildHasher;
-use yatzy::Dice;
+use serde::{Serialize, de::DeserializeOwned};
 
 use yatzy_compute_expected_values::{
-    FieldState, GameState, game_from_state, game_states_by_empty_field_count,
-    rational::{expected_value_2_rerolls, prob},
+    Fixed, GameState, Value, game_states_by_empty_field_count,
+    generate::compute_expected_values,
+    table::{self, Precision},
 };
 
-fn main() {
-    let states = game_states_by_empty_field_count();
+use crate::{
+    export::{ExportArgs, ImportArgs},
+    shard::{CoordinateArgs, WorkArgs},
+    verify::VerifyArgs,
+};
+
+mod export;
+mod shard;
+mod verify;
+
+#[derive(Clone, Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, value_enum, default_value_t)]
+    precision: PrecisionArg,
+    #[arg(short, long, default_value = "expected-values")]
+    output: PathBuf,
+    #[arg(long, default_value = "checkpoint")]
+    checkpoint: PathBuf,
+    #[arg(long, default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(1..))]
+    batch_size: u64,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+enum PrecisionArg {
+    #[default]
+    Rational,
+    F64,
+    F32,
+    Fixed,
+}
+
+impl From<PrecisionArg> for Precision {
+    fn from(precision: PrecisionArg) -> Self {
+        match precision {
+            PrecisionArg::Rational => Self::Rational,
+            PrecisionArg::F64 => Self::F64,
+            PrecisionArg::F32 => Self::F32,
+            PrecisionArg::Fixed => Self::Fixed,
+        }
+    }
+}
 
-    let mut expected_values = match std::fs::read("checkpoint") {
-        Ok(bytes) => postcard::from_bytes(&bytes).unwrap(),
-        Err(error) => {
-            eprintln!("could not open `checkpoint`: {error}");
-            HashMap::with_capacity_and_hasher(958_974, FxBuildHasher)
+#[derive(Clone, Debug, clap::Subcommand)]
+enum Command {
+    Coordinate(CoordinateArgs),
+    Work(WorkArgs),
+    Verify(VerifyArgs),
+    Export(ExportArgs),
+    Import(ImportArgs),
+}
+
+fn main() {
+    let args = Args::parse();
+    let precision = Precision::from(args.precision);
+    let result = match &args.command {
+        None => {
+            match precision {
+                Precision::Rational => run::<Ratio<BigUint>>(&args),
+                Precision::F64 => run::<f64>(&args),
+                Precision::F32 => run::<f32>(&args),
+                Precision::Fixed => run::<Fixed>(&args),
+            }
+            Ok(())
+        }
+        Some(Command::Coordinate(coordinate_args)) => match precision {
+            Precision::Rational => {
+                shard::coordinate::<Ratio<BigUint>>(coordinate_args, precision, &args.output)
+            }
+            Precision::F64 => shard::coordinate::<f64>(coordinate_args, precision, &args.output),
+            Precision::F32 => shard::coordinate::<f32>(coordinate_args, precision, &args.output),
+            Precision::Fixed => {
+                shard::coordinate::<Fixed>(coordinate_args, precision, &args.output)
+            }
+        }
+        .map_err(|error| error.to_string()),
+        // workers use whatever precision the coordinator chose
+        Some(Command::Work(work_args)) => match shard::read_precision(work_args.directory()) {
+            Ok(Precision::Rational) => shard::work::<Ratio<BigUint>>(work_args),
+            Ok(Precision::F64) => shard::work::<f64>(work_args),
+            Ok(Precision::F32) => shard::work::<f32>(work_args),
+            Ok(Precision::Fixed) => shard::work::<Fixed>(work_args),
+            Err(error) => Err(error),
+        }
+        .map_err(|error| error.to_string()),
+        Some(Command::Verify(verify_args)) => {
+            let result = match precision {
+                Precision::Rational => verify::verify::<Ratio<BigUint>>(verify_args),
+                Precision::F64 => verify::verify::<f64>(verify_args),
+                Precision::F32 => verify::verify::<f32>(verify_args),
+                Precision::Fixed => verify::verify::<Fixed>(verify_args),
+            };
+            match result {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(String::from("verification failed")),
+                Err(error) => Err(error),
+            }
+        }
+        Some(Command::Export(export_args)) => match precision {
+            Precision::Rational => export::export::<Ratio<BigUint>>(export_args),
+            Precision::F64 => export::export::<f64>(export_args),
+            Precision::F32 => export::export::<f32>(export_args),
+            Precision::Fixed => export::export::<Fixed>(export_args),
+        }
+        .map_err(|error| error.to_string()),
+        Some(Command::Import(import_args)) => match precision {
+            Precision::Rational => export::import::<Ratio<BigUint>>(import_args),
+            Precision::F64 => export::import::<f64>(import_args),
+            Precision::F32 => export::import::<f32>(import_args),
+            Precision::Fixed => export::import::<Fixed>(import_args),
         }
+        .map_err(|error| error.to_string()),
     };
+    if let Err(error) = result {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+fn write_values<V: Value + Serialize>(
+    path: &Path,
+    expected_values: &HashMap<GameState, V, FxBuildHasher>,
+) -> std::io::Result<()> {
+    let bytes = table::encode(expected_values).map_err(std::io::Error::other)?;
+    // write next to the target first so that an interrupted write never
+    // leaves a truncated file behind
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    std::fs::write(&temporary, bytes)?;
+    std::fs::rename(&temporary, path)
+}
+
+fn run<V>(args: &Args)
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync
+        + Display
+        + Serialize
+        + DeserializeOwned,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+{
+    let states = game_states_by_empty_field_count();
 
-    let first_computation: HashMap<GameState, Ratio<BigUint>> =
-        postcard::from_bytes(&std::fs::read("expected-values").unwrap()).unwrap();
+    let checkpoint = args.checkpoint.display();
+    let mut expected_values: HashMap<GameState, V, FxBuildHasher> =
+        match std::fs::read(&args.checkpoint) {
+            Ok(bytes) => match table::decode(&bytes) {
+                Ok(map) => map,
+                Err(error) => {
+                    eprintln!("failed to parse `{checkpoint}`: {error}");
+                    std::process::exit(1);
+                }
+            },
+            Err(error) => {
+                eprintln!("could not open `{checkpoint}`: {error}");
+                HashMap::with_capacity_and_hasher(958_974, FxBuildHasher)
+            }
+        };
 
     if !expected_values.is_empty() {
         eprintln!("loaded checkpoint with {} states", expected_values.len());
     }
 
-    let initial_state = GameState {
-        numbers_total: 0,
-        ones: FieldState::Empty,
-        twos: FieldState::Empty,
-        threes: FieldState::Empty,
-        fours: FieldState::Empty,
-        fives: FieldState::Empty,
-        sixes: FieldState::Empty,
-        one_pair: FieldState::Empty,
-        two_pairs: FieldState::Empty,
-        three_of_a_kind: FieldState::Empty,
-        four_of_a_kind: FieldState::Empty,
-        small_straight: FieldState::Empty,
-        large_straight: FieldState::Empty,
-        full_house: FieldState::Empty,
-        chance: FieldState::Empty,
-        yatzy: FieldState::Empty,
-    };
-    if let Some(value) = first_computation.get(&initial_state) {
-        eprintln!("expected value for the entire game: {value}");
-    }
-
     let mut total_states = 0;
     for n in 1..=15 {
-        let state_count = states.get(&n).unwrap().len();
-        total_states += state_count;
+        let states = states.get(&n).unwrap();
+        total_states += states.len();
         eprintln!(
             "calculating expected values for game states with {} empty field(s) ({} states)",
-            n, state_count,
+            n,
+            states.len(),
         );
+        let mut computed = false;
         loop {
-            let states = states.get(&n).unwrap();
-            let new_values = compute_expected_values(states, &expected_values, 10_000);
+            let new_values =
+                compute_expected_values(states, &expected_values, args.batch_size as usize);
             if new_values.is_empty() {
                 break;
-            } else {
-                eprint!("\n");
-            }
-
-            for (k, v) in &new_values {
-                assert!(v == first_computation.get(&k).unwrap());
             }
-
+            eprintln!();
             expected_values.extend(new_values);
+            computed = true;
+        }
 
-            continue;
-            let bytes = postcard::to_allocvec(&expected_values).unwrap();
-            let filename = format!("checkpoint-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
-            match OpenOptions::new()
-                .create_new(true)
-                .write(true)
-                .open(&filename)
-            {
-                Ok(mut file) => match file.write_all(&bytes) {
-                    Ok(()) => {
-                        eprintln!("checkpoint written to {filename}");
-                    }
-                    Err(error) => {
-                        eprintln!("failed to write checkpoint: {error}");
-                    }
-                },
+        if computed {
+            match write_values(&args.checkpoint, &expected_values) {
+                Ok(()) => {
+                    eprintln!("checkpoint written to {checkpoint}");
+                }
                 Err(error) => {
                     eprintln!("failed to write checkpoint: {error}");
                 }
@@ -103,35 +223,21 @@ fn main() {
         }
     }
     eprintln!("{total_states} total states");
+
+    finish(&expected_values, &args.output);
 }
 
-pub fn compute_expected_values<S1: BuildHasher, S2: BuildHasher + Sync>(
-    states: &HashSet<GameState, S1>,
-    expected_values: &HashMap<GameState, Ratio<BigUint>, S2>,
-    count: usize,
-) -> HashMap<GameState, Ratio<BigUint>, FxBuildHasher> {
-    let mut i = 0;
-    states
-        .into_iter()
-        .filter(|state| !expected_values.contains_key(state))
-        .take(count)
-        .map(|&state| {
-            let cache = papaya::HashMap::with_hasher(FxBuildHasher);
-            let value = prob::ROLL_5_PROB
-                .into_par_iter()
-                .map(|(dice_array, prob)| {
-                    let dice = Dice::new_raw(dice_array);
-                    let game = game_from_state(state, dice);
-                    let (numer, denom) = prob.into_raw();
-                    Ratio::new(numer.into(), denom.into())
-                        * expected_value_2_rerolls(game, expected_values, &cache)
-                })
-                .sum();
-            if i % 100 == 0 {
-                eprint!(".");
-            }
-            i += 1;
-            (state, value)
-        })
-        .collect()
+fn finish<V: Value + Display + Serialize>(
+    expected_values: &HashMap<GameState, V, FxBuildHasher>,
+    output: &Path,
+) {
+    let initial_state = GameState::initial();
+    if let Some(value) = expected_values.get(&initial_state) {
+        eprintln!("expected value for the entire game: {value}");
+    }
+
+    if let Err(error) = write_values(output, expected_values) {
+        eprintln!("failed to write `{}`: {error}", output.display());
+        std::process::exit(1);
+    }
 }
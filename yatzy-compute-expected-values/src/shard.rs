@@ -0,0 +1,347 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs::OpenOptions,
+    io::{self, ErrorKind},
+    iter::Sum,
+    ops::{AddAssign, Mul},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use rustc_hash::FxBuildHasher;
+use serde::{Serialize, de::DeserializeOwned};
+
+use yatzy_compute_expected_values::{
+    GameState, Value, game_states_by_empty_field_count, generate::compute_expected_values,
+    table::Precision,
+};
+
+use crate::finish;
+
+// the shared directory contains
+//   precision                      precision chosen by the coordinator
+//   values                         merged values of every finished layer
+//   layer-NN/shard-NNNN.task       game states of one shard
+//   layer-NN/shard-NNNN.claim      name of the worker computing the shard,
+//                                  rewritten after every batch as a heartbeat
+//   layer-NN/shard-NNNN.out        values computed for the shard
+//   done                           written once the whole table is merged
+
+const WORKER_BATCH_SIZE: usize = 100;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct CoordinateArgs {
+    directory: PathBuf,
+    #[arg(long, default_value_t = 1_000, value_parser = clap::value_parser!(u64).range(1..))]
+    shard_size: u64,
+    #[arg(long, default_value_t = 600)]
+    stale_after_seconds: u64,
+    #[arg(long, default_value_t = 5)]
+    poll_interval_seconds: u64,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct WorkArgs {
+    directory: PathBuf,
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long, default_value_t = 5)]
+    poll_interval_seconds: u64,
+}
+
+impl WorkArgs {
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShardError {
+    #[error("`{0}`: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("failed to parse `{0}`: {1}")]
+    Parse(PathBuf, postcard::Error),
+    #[error("invalid precision `{0}` in the shared directory")]
+    InvalidPrecision(String),
+    #[error("shared directory uses precision `{0}`")]
+    PrecisionMismatch(String),
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<T, ShardError> {
+    let bytes = std::fs::read(path).map_err(|error| ShardError::Io(path.to_owned(), error))?;
+    postcard::from_bytes(&bytes).map_err(|error| ShardError::Parse(path.to_owned(), error))
+}
+
+fn write<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), ShardError> {
+    let bytes = postcard::to_allocvec(value)
+        .map_err(|error| ShardError::Io(path.to_owned(), io::Error::other(error)))?;
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temporary, bytes)
+        .and_then(|()| std::fs::rename(&temporary, path))
+        .map_err(|error| ShardError::Io(path.to_owned(), error))
+}
+
+fn layer_directory(directory: &Path, layer: u8) -> PathBuf {
+    directory.join(format!("layer-{layer:02}"))
+}
+
+fn shard_path(directory: &Path, layer: u8, shard: usize, extension: &str) -> PathBuf {
+    layer_directory(directory, layer).join(format!("shard-{shard:04}.{extension}"))
+}
+
+fn task_paths(directory: &Path, layer: u8) -> Result<Vec<PathBuf>, ShardError> {
+    let layer_directory = layer_directory(directory, layer);
+    let entries = match std::fs::read_dir(&layer_directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(ShardError::Io(layer_directory, error)),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|error| ShardError::Io(layer_directory.clone(), error))?
+            .path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "task")
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+pub fn read_precision(directory: &Path) -> Result<Precision, ShardError> {
+    let path = directory.join("precision");
+    let name = std::fs::read_to_string(&path).map_err(|error| ShardError::Io(path, error))?;
+    let name = name.trim();
+    Precision::from_name(name).ok_or_else(|| ShardError::InvalidPrecision(String::from(name)))
+}
+
+pub fn coordinate<V>(
+    args: &CoordinateArgs,
+    precision: Precision,
+    output: &Path,
+) -> Result<(), ShardError>
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync
+        + Display
+        + Serialize
+        + DeserializeOwned,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+{
+    let directory = &args.directory;
+    std::fs::create_dir_all(directory).map_err(|error| ShardError::Io(directory.clone(), error))?;
+    match read_precision(directory) {
+        Ok(existing) => {
+            if existing != precision {
+                return Err(ShardError::PrecisionMismatch(existing.to_string()));
+            }
+        }
+        Err(ShardError::Io(_, error)) if error.kind() == ErrorKind::NotFound => {
+            let path = directory.join("precision");
+            std::fs::write(&path, precision.name()).map_err(|error| ShardError::Io(path, error))?;
+        }
+        Err(error) => return Err(error),
+    }
+
+    let values_path = directory.join("values");
+    let mut expected_values: HashMap<GameState, V, FxBuildHasher> = if values_path.exists() {
+        read(&values_path)?
+    } else {
+        HashMap::with_capacity_and_hasher(958_974, FxBuildHasher)
+    };
+    if !expected_values.is_empty() {
+        eprintln!("resuming with {} merged states", expected_values.len());
+    }
+
+    let states = game_states_by_empty_field_count();
+    let stale_after = Duration::from_secs(args.stale_after_seconds);
+    let poll_interval = Duration::from_secs(args.poll_interval_seconds);
+    for layer in 1..=15 {
+        let remaining: Vec<GameState> = states
+            .get(&layer)
+            .unwrap()
+            .iter()
+            .filter(|state| !expected_values.contains_key(state))
+            .copied()
+            .collect();
+        if remaining.is_empty() {
+            continue;
+        }
+
+        // tasks written by an earlier run of the coordinator are reused as is
+        let mut tasks = task_paths(directory, layer)?;
+        if tasks.is_empty() {
+            let layer_directory = layer_directory(directory, layer);
+            std::fs::create_dir_all(&layer_directory)
+                .map_err(|error| ShardError::Io(layer_directory, error))?;
+            for (shard, states) in remaining.chunks(args.shard_size as usize).enumerate() {
+                let path = shard_path(directory, layer, shard, "task");
+                write(&path, states)?;
+                tasks.push(path);
+            }
+        }
+        eprintln!(
+            "layer {layer}: {} states in {} shards",
+            remaining.len(),
+            tasks.len(),
+        );
+
+        let mut merged = HashSet::new();
+        while merged.len() < tasks.len() {
+            for task in &tasks {
+                if merged.contains(task) {
+                    continue;
+                }
+                let output = task.with_extension("out");
+                if output.exists() {
+                    let task_states: Vec<GameState> = read(task)?;
+                    let values: HashMap<GameState, V, FxBuildHasher> = read(&output)?;
+                    if values.len() != task_states.len()
+                        || !task_states.iter().all(|state| values.contains_key(state))
+                    {
+                        // computed against a different task; let it be redone
+                        eprintln!("discarding invalid output `{}`", output.display());
+                        _ = std::fs::remove_file(&output);
+                        _ = std::fs::remove_file(task.with_extension("claim"));
+                        continue;
+                    }
+                    expected_values.extend(values);
+                    merged.insert(task.clone());
+                    eprintln!(
+                        "layer {layer}: merged {} ({}/{})",
+                        output.display(),
+                        merged.len(),
+                        tasks.len(),
+                    );
+                    continue;
+                }
+
+                let claim = task.with_extension("claim");
+                let modified = std::fs::metadata(&claim).and_then(|metadata| metadata.modified());
+                if let Ok(modified) = modified {
+                    let age = SystemTime::now()
+                        .duration_since(modified)
+                        .unwrap_or(Duration::ZERO);
+                    if age > stale_after {
+                        eprintln!("releasing stale claim `{}`", claim.display());
+                        _ = std::fs::remove_file(&claim);
+                    }
+                }
+            }
+            if merged.len() < tasks.len() {
+                thread::sleep(poll_interval);
+            }
+        }
+
+        write(&values_path, &expected_values)?;
+        let layer_directory = layer_directory(directory, layer);
+        std::fs::remove_dir_all(&layer_directory)
+            .map_err(|error| ShardError::Io(layer_directory, error))?;
+    }
+
+    finish(&expected_values, output);
+    let path = directory.join("done");
+    std::fs::write(&path, "").map_err(|error| ShardError::Io(path, error))
+}
+
+fn claim_next(directory: &Path, name: &str) -> Result<Option<(u8, PathBuf)>, ShardError> {
+    for layer in 1..=15 {
+        for task in task_paths(directory, layer)? {
+            if task.with_extension("out").exists() {
+                continue;
+            }
+            let claim = task.with_extension("claim");
+            match OpenOptions::new().write(true).create_new(true).open(&claim) {
+                Ok(_) => {
+                    std::fs::write(&claim, name).map_err(|error| ShardError::Io(claim, error))?;
+                    return Ok(Some((layer, task)));
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+                Err(error) => return Err(ShardError::Io(claim, error)),
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn work<V>(args: &WorkArgs) -> Result<(), ShardError>
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync
+        + Serialize
+        + DeserializeOwned,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+{
+    let directory = &args.directory;
+    let name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("worker-{}", std::process::id()));
+    let poll_interval = Duration::from_secs(args.poll_interval_seconds);
+
+    // values of the finished layers, reloaded whenever a shard of a new layer is claimed
+    let mut loaded_layer = None;
+    let mut expected_values: HashMap<GameState, V, FxBuildHasher> = HashMap::default();
+    loop {
+        if directory.join("done").exists() {
+            eprintln!("{name}: all shards are done");
+            return Ok(());
+        }
+        let Some((layer, task)) = claim_next(directory, &name)? else {
+            thread::sleep(poll_interval);
+            continue;
+        };
+        if loaded_layer != Some(layer) {
+            expected_values = if layer == 1 {
+                HashMap::default()
+            } else {
+                read(&directory.join("values"))?
+            };
+            loaded_layer = Some(layer);
+        }
+
+        eprintln!("{name}: computing `{}`", task.display());
+        let claim = task.with_extension("claim");
+        let states: Vec<GameState> = read(&task)?;
+        let mut values = HashMap::with_capacity_and_hasher(states.len(), FxBuildHasher);
+        for batch in states.chunks(WORKER_BATCH_SIZE) {
+            let batch: HashSet<GameState, FxBuildHasher> = batch.iter().copied().collect();
+            values.extend(compute_expected_values(
+                &batch,
+                &expected_values,
+                batch.len(),
+            ));
+            // a heartbeat, so that the coordinator does not hand the shard out again
+            _ = std::fs::write(&claim, &name);
+        }
+        eprintln!();
+        if !task.exists() {
+            // released as stale and merged from another worker in the meantime
+            eprintln!("{name}: `{}` is already merged", task.display());
+            continue;
+        }
+        write(&task.with_extension("out"), &values)?;
+        _ = std::fs::remove_file(&claim);
+    }
+}
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, fmt, hash::BuildHasher};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{GameState, Value};
+
+// tables start with these bytes and a precision tag, so that values written
+// with one precision are never decoded as another; tables from before the
+// header hold rational values only
+const MAGIC: &[u8; 4] = b"YZEV";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Precision {
+    #[default]
+    Rational,
+    F64,
+    F32,
+    Fixed,
+}
+
+impl Precision {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rational => "rational",
+            Self::F64 => "f64",
+            Self::F32 => "f32",
+            Self::Fixed => "fixed",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rational" => Some(Self::Rational),
+            "f64" => Some(Self::F64),
+            "f32" => Some(Self::F32),
+            "fixed" => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            Self::Rational => 0,
+            Self::F64 => 1,
+            Self::F32 => 2,
+            Self::Fixed => 3,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Rational),
+            1 => Some(Self::F64),
+            2 => Some(Self::F32),
+            3 => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TableError {
+    #[error("unknown precision tag {0}")]
+    InvalidPrecision(u8),
+    #[error("table has precision `{found}`, expected `{expected}`")]
+    PrecisionMismatch {
+        expected: Precision,
+        found: Precision,
+    },
+    #[error(transparent)]
+    Postcard(#[from] postcard::Error),
+}
+
+pub fn encode<V, S>(expected_values: &HashMap<GameState, V, S>) -> Result<Vec<u8>, postcard::Error>
+where
+    V: Value + Serialize,
+{
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(V::PRECISION.tag());
+    postcard::to_extend(expected_values, bytes)
+}
+
+fn split_header(bytes: &[u8]) -> Result<(Precision, &[u8]), TableError> {
+    match bytes.strip_prefix(MAGIC) {
+        Some([tag, values @ ..]) => Precision::from_tag(*tag)
+            .map(|precision| (precision, values))
+            .ok_or(TableError::InvalidPrecision(*tag)),
+        _ => Ok((Precision::Rational, bytes)),
+    }
+}
+
+pub fn precision(bytes: &[u8]) -> Result<Precision, TableError> {
+    split_header(bytes).map(|(precision, _)| precision)
+}
+
+pub fn decode<V, S>(bytes: &[u8]) -> Result<HashMap<GameState, V, S>, TableError>
+where
+    V: Value + DeserializeOwned,
+    S: BuildHasher + Default,
+{
+    let (found, values) = split_header(bytes)?;
+    if found != V::PRECISION {
+        return Err(TableError::PrecisionMismatch {
+            expected: V::PRECISION,
+            found,
+        });
+    }
+    Ok(postcard::from_bytes(values)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use num_rational::Ratio;
+    use rustc_hash::FxBuildHasher;
+
+    use super::*;
+
+    #[test]
+    fn headerless_tables_decode_as_rational() {
+        let mut expected_values = HashMap::with_hasher(FxBuildHasher);
+        expected_values.insert(
+            GameState::initial(),
+            Ratio::new(BigUint::from(2_487_u16), BigUint::from(10_u8)),
+        );
+        let bytes = postcard::to_allocvec(&expected_values).unwrap();
+
+        assert_eq!(precision(&bytes).unwrap(), Precision::Rational);
+        let decoded: HashMap<GameState, Ratio<BigUint>, FxBuildHasher> = decode(&bytes).unwrap();
+        assert_eq!(decoded, expected_values);
+        assert!(matches!(
+            decode::<f64, FxBuildHasher>(&bytes),
+            Err(TableError::PrecisionMismatch {
+                expected: Precision::F64,
+                found: Precision::Rational,
+            })
+        ));
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul},
+};
+
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use num_rational::{BigRational, Ratio};
+use num_traits::ToPrimitive as _;
+use serde::{Deserialize, Serialize};
+use yatzy::{Die, transitions};
+
+use crate::{prob, table::Precision};
+
+pub trait Value: Sized {
+    const PRECISION: Precision;
+
+    fn from_u8(input: u8) -> Self;
+    fn from_u16(input: u16) -> Self;
+    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)>;
+    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)>;
+    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)>;
+    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)>;
+    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)>;
+    fn transition_prob<'a>() -> &'a Vec<Vec<(usize, Self)>>;
+    fn zero() -> Self;
+    fn to_f64(&self) -> f64;
+    fn to_ratio(&self) -> Ratio<BigUint>;
+    fn from_ratio(ratio: &Ratio<BigUint>) -> Self;
+}
+
+// 32.32 fixed point; scores stay far below 2^32 and products are taken in
+// 128 bits before shifting back
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+pub struct Fixed(u64);
+
+impl Fixed {
+    const FRACTION_BITS: u32 = 32;
+
+    fn from_prob(ratio: Ratio<u16>) -> Self {
+        let (numer, denom) = ratio.into_raw();
+        Self((u64::from(numer) << Self::FRACTION_BITS) / u64::from(denom))
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Mul<Fixed> for &Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: Fixed) -> Fixed {
+        let product = u128::from(self.0) * u128::from(other.0);
+        Fixed((product >> Fixed::FRACTION_BITS) as u64)
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self(0), |a, b| a + b)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Value::to_f64(self))
+    }
+}
+
+impl Value for f64 {
+    const PRECISION: Precision = Precision::F64;
+
+    fn from_u8(input: u8) -> Self {
+        input.into()
+    }
+
+    fn from_u16(input: u16) -> Self {
+        input.into()
+    }
+
+    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
+        &ROLL_1_PROB_F64
+    }
+
+    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
+        &ROLL_2_PROB_F64
+    }
+
+    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
+        &ROLL_3_PROB_F64
+    }
+
+    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
+        &ROLL_4_PROB_F64
+    }
+
+    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
+        &ROLL_5_PROB_F64
+    }
+
+    fn transition_prob<'a>() -> &'a Vec<Vec<(usize, Self)>> {
+        &TRANSITION_PROB_F64
+    }
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+
+    fn to_ratio(&self) -> Ratio<BigUint> {
+        float_to_ratio(*self)
+    }
+
+    fn from_ratio(ratio: &Ratio<BigUint>) -> Self {
+        num_traits::ToPrimitive::to_f64(ratio).unwrap()
+    }
+}
+
+impl Value for f32 {
+    const PRECISION: Precision = Precision::F32;
+
+    fn from_u8(input: u8) -> Self {
+        input.into()
+    }
+
+    fn from_u16(input: u16) -> Self {
+        input.into()
+    }
+
+    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
+        &ROLL_1_PROB_F32
+    }
+
+    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
+        &ROLL_2_PROB_F32
+    }
+
+    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
+        &ROLL_3_PROB_F32
+    }
+
+    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
+        &ROLL_4_PROB_F32
+    }
+
+    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
+        &ROLL_5_PROB_F32
+    }
+
+    fn transition_prob<'a>() -> &'a Vec<Vec<(usize, Self)>> {
+        &TRANSITION_PROB_F32
+    }
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn to_f64(&self) -> f64 {
+        f64::from(*self)
+    }
+
+    fn to_ratio(&self) -> Ratio<BigUint> {
+        float_to_ratio(f64::from(*self))
+    }
+
+    fn from_ratio(ratio: &Ratio<BigUint>) -> Self {
+        num_traits::ToPrimitive::to_f32(ratio).unwrap()
+    }
+}
+
+impl Value for Fixed {
+    const PRECISION: Precision = Precision::Fixed;
+
+    fn from_u8(input: u8) -> Self {
+        Self(u64::from(input) << Self::FRACTION_BITS)
+    }
+
+    fn from_u16(input: u16) -> Self {
+        Self(u64::from(input) << Self::FRACTION_BITS)
+    }
+
+    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
+        &ROLL_1_PROB_FIXED
+    }
+
+    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
+        &ROLL_2_PROB_FIXED
+    }
+
+    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
+        &ROLL_3_PROB_FIXED
+    }
+
+    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
+        &ROLL_4_PROB_FIXED
+    }
+
+    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
+        &ROLL_5_PROB_FIXED
+    }
+
+    fn transition_prob<'a>() -> &'a Vec<Vec<(usize, Self)>> {
+        &TRANSITION_PROB_FIXED
+    }
+
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0 as f64 / (1_u64 << Self::FRACTION_BITS) as f64
+    }
+
+    fn to_ratio(&self) -> Ratio<BigUint> {
+        Ratio::new(
+            BigUint::from(self.0),
+            BigUint::from(1_u64) << Self::FRACTION_BITS,
+        )
+    }
+
+    fn from_ratio(ratio: &Ratio<BigUint>) -> Self {
+        let raw = (ratio.numer() << Self::FRACTION_BITS) / ratio.denom();
+        Self(raw.to_u64().unwrap())
+    }
+}
+
+impl Value for Ratio<BigUint> {
+    const PRECISION: Precision = Precision::Rational;
+
+    fn from_u8(input: u8) -> Self {
+        Ratio::from(BigUint::from(input))
+    }
+
+    fn from_u16(input: u16) -> Self {
+        Ratio::from(BigUint::from(input))
+    }
+
+    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
+        &ROLL_1_PROB_RATIO
+    }
+
+    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
+        &ROLL_2_PROB_RATIO
+    }
+
+    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
+        &ROLL_3_PROB_RATIO
+    }
+
+    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
+        &ROLL_4_PROB_RATIO
+    }
+
+    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
+        &ROLL_5_PROB_RATIO
+    }
+
+    fn transition_prob<'a>() -> &'a Vec<Vec<(usize, Self)>> {
+        &TRANSITION_PROB_RATIO
+    }
+
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn to_f64(&self) -> f64 {
+        num_traits::ToPrimitive::to_f64(self).unwrap()
+    }
+
+    fn to_ratio(&self) -> Ratio<BigUint> {
+        self.clone()
+    }
+
+    fn from_ratio(ratio: &Ratio<BigUint>) -> Self {
+        ratio.clone()
+    }
+}
+
+// every finite float is a dyadic rational, so this is exact
+fn float_to_ratio(value: f64) -> Ratio<BigUint> {
+    let (numer, denom) = BigRational::from_float(value).unwrap().into_raw();
+    Ratio::new(numer.to_biguint().unwrap(), denom.to_biguint().unwrap())
+}
+
+fn convert_prob_to_ratio(ratio: Ratio<u16>) -> Ratio<BigUint> {
+    let (numer, denom) = ratio.into_raw();
+    Ratio::new(numer.into(), denom.into())
+}
+
+// the transition table for every keep index, with probabilities converted once
+fn transition_prob<V>(convert: fn(Ratio<u16>) -> V) -> Vec<Vec<(usize, V)>> {
+    (0..transitions::KEEP_COUNT)
+        .map(|keep| {
+            transitions::transitions(keep)
+                .iter()
+                .map(|&(dice, prob)| (dice, convert(prob)))
+                .collect()
+        })
+        .collect()
+}
+
+lazy_static! {
+    static ref ROLL_1_PROB_RATIO: Vec<([Die; 1], Ratio<BigUint>)> = prob::ROLL_1_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
+        .collect();
+    static ref ROLL_2_PROB_RATIO: Vec<([Die; 2], Ratio<BigUint>)> = prob::ROLL_2_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
+        .collect();
+    static ref ROLL_3_PROB_RATIO: Vec<([Die; 3], Ratio<BigUint>)> = prob::ROLL_3_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
+        .collect();
+    static ref ROLL_4_PROB_RATIO: Vec<([Die; 4], Ratio<BigUint>)> = prob::ROLL_4_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
+        .collect();
+    static ref ROLL_5_PROB_RATIO: Vec<([Die; 5], Ratio<BigUint>)> = prob::ROLL_5_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
+        .collect();
+    static ref ROLL_1_PROB_F64: Vec<([Die; 1], f64)> = prob::ROLL_1_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
+        .collect();
+    static ref ROLL_2_PROB_F64: Vec<([Die; 2], f64)> = prob::ROLL_2_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
+        .collect();
+    static ref ROLL_3_PROB_F64: Vec<([Die; 3], f64)> = prob::ROLL_3_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
+        .collect();
+    static ref ROLL_4_PROB_F64: Vec<([Die; 4], f64)> = prob::ROLL_4_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
+        .collect();
+    static ref ROLL_5_PROB_F64: Vec<([Die; 5], f64)> = prob::ROLL_5_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
+        .collect();
+    static ref ROLL_1_PROB_F32: Vec<([Die; 1], f32)> = prob::ROLL_1_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f32().unwrap()))
+        .collect();
+    static ref ROLL_2_PROB_F32: Vec<([Die; 2], f32)> = prob::ROLL_2_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f32().unwrap()))
+        .collect();
+    static ref ROLL_3_PROB_F32: Vec<([Die; 3], f32)> = prob::ROLL_3_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f32().unwrap()))
+        .collect();
+    static ref ROLL_4_PROB_F32: Vec<([Die; 4], f32)> = prob::ROLL_4_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f32().unwrap()))
+        .collect();
+    static ref ROLL_5_PROB_F32: Vec<([Die; 5], f32)> = prob::ROLL_5_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, prob.to_f32().unwrap()))
+        .collect();
+    static ref ROLL_1_PROB_FIXED: Vec<([Die; 1], Fixed)> = prob::ROLL_1_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, Fixed::from_prob(prob)))
+        .collect();
+    static ref ROLL_2_PROB_FIXED: Vec<([Die; 2], Fixed)> = prob::ROLL_2_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, Fixed::from_prob(prob)))
+        .collect();
+    static ref ROLL_3_PROB_FIXED: Vec<([Die; 3], Fixed)> = prob::ROLL_3_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, Fixed::from_prob(prob)))
+        .collect();
+    static ref ROLL_4_PROB_FIXED: Vec<([Die; 4], Fixed)> = prob::ROLL_4_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, Fixed::from_prob(prob)))
+        .collect();
+    static ref ROLL_5_PROB_FIXED: Vec<([Die; 5], Fixed)> = prob::ROLL_5_PROB
+        .into_iter()
+        .map(|(dice, prob)| (dice, Fixed::from_prob(prob)))
+        .collect();
+    static ref TRANSITION_PROB_RATIO: Vec<Vec<(usize, Ratio<BigUint>)>> =
+        transition_prob(convert_prob_to_ratio);
+    static ref TRANSITION_PROB_F64: Vec<Vec<(usize, f64)>> =
+        transition_prob(|prob| prob.to_f64().unwrap());
+    static ref TRANSITION_PROB_F32: Vec<Vec<(usize, f32)>> =
+        transition_prob(|prob| prob.to_f32().unwrap());
+    static ref TRANSITION_PROB_FIXED: Vec<Vec<(usize, Fixed)>> = transition_prob(Fixed::from_prob);
+}
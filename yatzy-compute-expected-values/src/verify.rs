@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    iter::Sum,
+    ops::{AddAssign, Mul},
+    path::PathBuf,
+};
+
+use rustc_hash::FxBuildHasher;
+use serde::de::DeserializeOwned;
+use yatzy::Combo;
+
+use yatzy_compute_expected_values::{
+    FieldState, GameState, Value, game_states_by_empty_field_count,
+    generate::state_value,
+    table::{self, Precision},
+};
+
+// the optimal expected score of Scandinavian Yatzy as published by earlier solvers
+const PUBLISHED_INITIAL_VALUE: f64 = 248.63;
+const PUBLISHED_INITIAL_VALUE_TOLERANCE: f64 = 0.005;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct VerifyArgs {
+    #[arg(default_value = "expected-values")]
+    expected_values: PathBuf,
+    #[arg(long)]
+    layer: Vec<u8>,
+    #[arg(long)]
+    tolerance: Option<f64>,
+    #[arg(long, default_value_t = 20)]
+    max_reports: usize,
+}
+
+struct Report {
+    max_reports: usize,
+    inconsistencies: usize,
+}
+
+impl Report {
+    fn inconsistent(&mut self, check: &str, state: GameState, message: String) {
+        if self.inconsistencies < self.max_reports {
+            println!("{check}: {state:?}: {message}");
+        } else if self.inconsistencies == self.max_reports {
+            println!("further inconsistencies are only counted");
+        }
+        self.inconsistencies += 1;
+    }
+}
+
+fn max_points(combo: Combo) -> u8 {
+    match combo {
+        Combo::Ones => 5,
+        Combo::Twos => 10,
+        Combo::Threes => 15,
+        Combo::Fours => 20,
+        Combo::Fives => 25,
+        Combo::Sixes => 30,
+        Combo::OnePair => 12,
+        Combo::TwoPairs => 22,
+        Combo::ThreeOfAKind => 18,
+        Combo::FourOfAKind => 24,
+        Combo::SmallStraight => 15,
+        Combo::LargeStraight => 20,
+        Combo::FullHouse => 28,
+        Combo::Chance => 30,
+        Combo::Yatzy => 50,
+    }
+}
+
+// rounding errors accumulated over a whole table; rationals are exact
+fn default_tolerance(precision: Precision) -> f64 {
+    match precision {
+        Precision::Rational => 0.0,
+        Precision::F64 => 1e-9,
+        Precision::F32 => 1e-3,
+        Precision::Fixed => 1e-6,
+    }
+}
+
+// whether a is below b by more than the tolerance
+fn below<V: Value + PartialOrd>(a: &V, b: &V, tolerance: f64) -> bool {
+    match V::PRECISION {
+        Precision::Rational => a < b,
+        Precision::F64 | Precision::F32 | Precision::Fixed => a.to_f64() + tolerance < b.to_f64(),
+    }
+}
+
+fn differs<V: Value + PartialEq>(a: &V, b: &V, tolerance: f64) -> bool {
+    match V::PRECISION {
+        Precision::Rational => a != b,
+        Precision::F64 | Precision::F32 | Precision::Fixed => {
+            (a.to_f64() - b.to_f64()).abs() > tolerance
+        }
+    }
+}
+
+fn bounds(state: GameState) -> (u16, u16) {
+    let mut upper = 0;
+    let mut possible_remaining_numbers = 0;
+    for combo in Combo::iter() {
+        if state.field(combo) == FieldState::Filled {
+            continue;
+        }
+        upper += u16::from(max_points(combo));
+        match combo {
+            Combo::Ones
+            | Combo::Twos
+            | Combo::Threes
+            | Combo::Fours
+            | Combo::Fives
+            | Combo::Sixes => {
+                possible_remaining_numbers += max_points(combo);
+            }
+            _ => {}
+        }
+    }
+    if state.numbers_total + possible_remaining_numbers >= 63 {
+        upper += 50;
+    }
+    let lower = if state.numbers_total == 63 { 50 } else { 0 };
+    (lower, upper)
+}
+
+pub fn verify<V>(args: &VerifyArgs) -> Result<bool, String>
+where
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + Sum
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync
+        + Display
+        + DeserializeOwned,
+    for<'a> &'a V: Mul<V>,
+    for<'a> <&'a V as Mul<V>>::Output: Send,
+{
+    let path = args.expected_values.display();
+    let bytes =
+        std::fs::read(&args.expected_values).map_err(|error| format!("`{path}`: {error}"))?;
+    let expected_values: HashMap<GameState, V, FxBuildHasher> =
+        table::decode(&bytes).map_err(|error| format!("failed to parse `{path}`: {error}"))?;
+
+    let tolerance = args
+        .tolerance
+        .unwrap_or_else(|| default_tolerance(V::PRECISION));
+    let mut report = Report {
+        max_reports: args.max_reports,
+        inconsistencies: 0,
+    };
+
+    let states = game_states_by_empty_field_count();
+    let mut total_states = 0;
+    for layer in 1..=15 {
+        for &state in states.get(&layer).unwrap() {
+            total_states += 1;
+            if !expected_values.contains_key(&state) {
+                report.inconsistent("completeness", state, String::from("missing"));
+            }
+        }
+    }
+    for &state in expected_values.keys() {
+        let layer = Combo::iter()
+            .filter(|&combo| state.field(combo) == FieldState::Empty)
+            .count() as u8;
+        let known = states
+            .get(&layer)
+            .is_some_and(|states| states.contains(&state));
+        if !known {
+            report.inconsistent("completeness", state, String::from("unreachable state"));
+        }
+    }
+    println!(
+        "completeness: {} of {total_states} states stored",
+        expected_values.len(),
+    );
+
+    for (&state, value) in &expected_values {
+        let (lower, upper) = bounds(state);
+        if below(value, &V::from_u16(lower), tolerance)
+            || below(&V::from_u16(upper), value, tolerance)
+        {
+            report.inconsistent(
+                "bounds",
+                state,
+                format!("{value} is outside [{lower}, {upper}]"),
+            );
+        }
+    }
+    println!("bounds: checked {} states", expected_values.len());
+
+    // a higher upper section total never hurts, and neither does having a
+    // field left to fill on the last turn
+    let mut comparisons = 0;
+    for (&state, value) in &expected_values {
+        if state.numbers_total < 63 {
+            let mut higher = state;
+            higher.numbers_total += 1;
+            if let Some(higher_value) = expected_values.get(&higher) {
+                comparisons += 1;
+                if below(higher_value, value, tolerance) {
+                    report.inconsistent(
+                        "monotonicity",
+                        state,
+                        format!(
+                            "{value} exceeds {higher_value} with numbers_total {}",
+                            higher.numbers_total,
+                        ),
+                    );
+                }
+            }
+        }
+        for combo in Combo::iter() {
+            if state.field(combo) == FieldState::Filled {
+                continue;
+            }
+            let mut filled = state;
+            filled.set_field(combo, FieldState::Filled);
+            if let Some(filled_value) = expected_values.get(&filled) {
+                comparisons += 1;
+                if below(value, filled_value, tolerance) {
+                    report.inconsistent(
+                        "monotonicity",
+                        state,
+                        format!("{value} is below {filled_value} with {combo:?} filled"),
+                    );
+                }
+            }
+        }
+    }
+    println!("monotonicity: {comparisons} comparisons");
+
+    let initial_state = GameState::initial();
+    match expected_values.get(&initial_state) {
+        Some(value) => {
+            let value = value.to_f64();
+            if (value - PUBLISHED_INITIAL_VALUE).abs() > PUBLISHED_INITIAL_VALUE_TOLERANCE {
+                report.inconsistent(
+                    "initial value",
+                    initial_state,
+                    format!("{value} differs from the published {PUBLISHED_INITIAL_VALUE}"),
+                );
+            }
+            println!("initial value: {value}");
+        }
+        None => {
+            println!("initial value: missing");
+        }
+    }
+
+    for layer in 1..=15 {
+        if !args.layer.is_empty() && !args.layer.contains(&layer) {
+            continue;
+        }
+        let mut checked = 0;
+        for &state in states.get(&layer).unwrap() {
+            let Some(stored) = expected_values.get(&state) else {
+                continue;
+            };
+            let recomputed = state_value(state, &expected_values);
+            if differs(&recomputed, stored, tolerance) {
+                report.inconsistent(
+                    "bellman",
+                    state,
+                    format!("stored {stored}, recomputed {recomputed}"),
+                );
+            }
+            checked += 1;
+        }
+        println!("bellman: layer {layer}: recomputed {checked} states");
+    }
+
+    println!("{} inconsistencies", report.inconsistencies);
+    Ok(report.inconsistencies == 0)
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+    ops::AddAssign,
+};
+
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+use serde::{Deserialize, Serialize};
+use yatzy::{Combo, Dice, Game};
+use yatzy_compute_expected_values::{game_from_state, state_from_game};
+
+use crate::{GameState, Value, turn::TurnTable};
+
+// expected points and probability of zero points in each combo, indexed in
+// the order of `Combo::iter`, and the bonus probability, counting only what is
+// still to be scored under optimal play
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Projection {
+    points: [f64; 15],
+    zero_probabilities: [f64; 15],
+    bonus_probability: f64,
+}
+
+// projections from the start of a turn in each state, computed once for the
+// whole table in layer order like the expected values themselves
+pub type Projections<S> = HashMap<GameState, Projection, S>;
+
+// the file starts with the value of the initial state in the table the
+// projections were computed from, so that a mismatched pair can be refused
+pub fn encode_projections<S>(
+    initial_value: f64,
+    projections: &Projections<S>,
+) -> Result<Vec<u8>, postcard::Error> {
+    postcard::to_allocvec(&(initial_value, projections))
+}
+
+pub fn decode_projections<S>(bytes: &[u8]) -> Result<(f64, Projections<S>), postcard::Error>
+where
+    S: BuildHasher + Default,
+{
+    postcard::from_bytes(bytes)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Analysis {
+    pub bonus_probability: f64,
+    pub expected_upper_total: f64,
+    // upper section points compared to three of each filled number
+    pub par: i16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct ComboProjection {
+    pub expected_points: f64,
+    pub zero_probability: f64,
+}
+
+fn face(combo: Combo) -> Option<u8> {
+    match combo {
+        Combo::Ones => Some(1),
+        Combo::Twos => Some(2),
+        Combo::Threes => Some(3),
+        Combo::Fours => Some(4),
+        Combo::Fives => Some(5),
+        Combo::Sixes => Some(6),
+        Combo::OnePair
+        | Combo::TwoPairs
+        | Combo::ThreeOfAKind
+        | Combo::FourOfAKind
+        | Combo::SmallStraight
+        | Combo::LargeStraight
+        | Combo::FullHouse
+        | Combo::Chance
+        | Combo::Yatzy => None,
+    }
+}
+
+fn upper_total(game: Game) -> u8 {
+    Combo::iter()
+        .filter(|&combo| face(combo).is_some())
+        .map(|combo| game.combo(combo).unwrap_or(0))
+        .sum()
+}
+
+fn par(game: Game) -> i16 {
+    Combo::iter()
+        .filter_map(|combo| Some((game.combo(combo)?, face(combo)?)))
+        .map(|(points, face)| i16::from(points) - 3 * i16::from(face))
+        .sum()
+}
+
+fn ended_projection(game: Game) -> Projection {
+    Projection {
+        bonus_probability: if game.has_bonus() { 1.0 } else { 0.0 },
+        ..Projection::default()
+    }
+}
+
+fn after_turn<S>(
+    game: Game,
+    outcomes: Vec<(Combo, Dice, f64)>,
+    projections: &Projections<S>,
+) -> Projection
+where
+    S: BuildHasher,
+{
+    let mut projection = Projection::default();
+    for (combo, dice, probability) in outcomes {
+        let points = combo.points(dice);
+        let mut game = game;
+        game.set_combo_raw(combo, Some(points));
+        let rest = if game.ended() {
+            ended_projection(game)
+        } else {
+            *projections.get(&state_from_game(game)).unwrap()
+        };
+        for (points, rest) in projection.points.iter_mut().zip(rest.points) {
+            *points += probability * rest;
+        }
+        for (zero_probability, rest) in projection
+            .zero_probabilities
+            .iter_mut()
+            .zip(rest.zero_probabilities)
+        {
+            *zero_probability += probability * rest;
+        }
+        projection.bonus_probability += probability * rest.bonus_probability;
+
+        let i = Combo::iter().position(|c| c == combo).unwrap();
+        projection.points[i] += probability * f64::from(points);
+        if points == 0 {
+            projection.zero_probabilities[i] += probability;
+        }
+    }
+    projection
+}
+
+// projections from the start of a turn for the states of one layer, given
+// the projections of every layer with fewer empty fields
+pub fn compute_projections<S1, S2, S3, V>(
+    states: &HashSet<GameState, S1>,
+    expected_values: &papaya::HashMap<GameState, V, S2>,
+    projections: &Projections<S3>,
+) -> Vec<(GameState, Projection)>
+where
+    S1: BuildHasher,
+    S2: BuildHasher + Sync,
+    S3: BuildHasher + Sync,
+    V: Value + AddAssign + Clone + Send + Sync,
+{
+    let states: Vec<GameState> = states.iter().copied().collect();
+    states
+        .into_par_iter()
+        .map(|state| {
+            // any game with the state is as good as another at the start of a turn
+            let game = game_from_state(state, Dice::new_raw([1, 1, 1, 1, 1]));
+            let outcomes = TurnTable::new(game, expected_values).turn_outcomes();
+            (state, after_turn(game, outcomes, projections))
+        })
+        .collect()
+}
+
+fn game_projection<S1, S2, V>(
+    game: Game,
+    expected_values: &papaya::HashMap<GameState, V, S1>,
+    projections: &Projections<S2>,
+) -> Projection
+where
+    S1: BuildHasher,
+    S2: BuildHasher,
+    V: Value + AddAssign + Clone,
+{
+    if game.ended() {
+        ended_projection(game)
+    } else {
+        let outcomes =
+            TurnTable::new(game, expected_values).outcomes(game.dice(), game.rerolls_left());
+        after_turn(game, outcomes, projections)
+    }
+}
+
+pub fn analyze<S1, S2, V>(
+    game: Game,
+    expected_values: &papaya::HashMap<GameState, V, S1>,
+    projections: &Projections<S2>,
+) -> Analysis
+where
+    S1: BuildHasher,
+    S2: BuildHasher,
+    V: Value + AddAssign + Clone,
+{
+    let projection = game_projection(game, expected_values, projections);
+    let upper_points: f64 = Combo::iter()
+        .zip(projection.points)
+        .filter_map(|(combo, points)| face(combo).map(|_| points))
+        .sum();
+    Analysis {
+        bonus_probability: projection.bonus_probability,
+        expected_upper_total: f64::from(upper_total(game)) + upper_points,
+        par: par(game),
+    }
+}
+
+// the final points of every combo in the order of `Combo::iter`, filled
+// combos included as they are
+pub fn project<S1, S2, V>(
+    game: Game,
+    expected_values: &papaya::HashMap<GameState, V, S1>,
+    projections: &Projections<S2>,
+) -> Vec<(Combo, ComboProjection)>
+where
+    S1: BuildHasher,
+    S2: BuildHasher,
+    V: Value + AddAssign + Clone,
+{
+    let projection = game_projection(game, expected_values, projections);
+    Combo::iter()
+        .enumerate()
+        .map(|(i, combo)| {
+            let combo_projection = match game.combo(combo) {
+                Some(points) => ComboProjection {
+                    expected_points: f64::from(points),
+                    zero_probability: if points == 0 { 1.0 } else { 0.0 },
+                },
+                None => ComboProjection {
+                    expected_points: projection.points[i],
+                    zero_probability: projection.zero_probabilities[i],
+                },
+            };
+            (combo, combo_projection)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng as _};
+    use rand_chacha::ChaCha8Rng;
+    use rustc_hash::FxBuildHasher;
+    use yatzy::transitions;
+    use yatzy_compute_expected_values::generate::state_value;
+
+    use super::*;
+
+    // the projected scoresheet adds up to the expected final score
+    #[test]
+    fn projection_sums_to_expected_score() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for rerolls_left in [0, 1, 2, 2] {
+            let mut game = Game::new_random(&mut rng);
+            let first = rng.random_range(0..15);
+            let second = (first + rng.random_range(1..15)) % 15;
+            // upper section points around par keep the bonus in play
+            for (i, combo) in Combo::iter().enumerate() {
+                if i != first && i != second {
+                    let points = match face(combo) {
+                        Some(face) => face * rng.random_range(2..=4),
+                        None => combo.points(Dice::new_random(&mut rng)),
+                    };
+                    game.set_combo_raw(combo, Some(points));
+                }
+            }
+            game.set_rerolls(rerolls_left);
+
+            // the states after this turn have a single empty field left
+            let mut states: HashSet<GameState, FxBuildHasher> = HashSet::default();
+            for combo in Combo::iter().filter(|&combo| game.combo(combo).is_none()) {
+                for &dice in transitions::all_dice() {
+                    let mut next = game;
+                    next.set_combo_raw(combo, Some(combo.points(dice)));
+                    states.insert(state_from_game(next));
+                }
+            }
+            let terminal: HashMap<GameState, f64, FxBuildHasher> = HashMap::default();
+            let expected_values = papaya::HashMap::with_hasher(FxBuildHasher);
+            for &state in &states {
+                expected_values
+                    .pin()
+                    .insert(state, state_value::<f64, _>(state, &terminal));
+            }
+            let mut projections: Projections<FxBuildHasher> = Projections::default();
+            let layer = compute_projections(&states, &expected_values, &projections);
+            projections.extend(layer);
+
+            let projected: f64 = project(game, &expected_values, &projections)
+                .iter()
+                .map(|(_, projection)| projection.expected_points)
+                .sum::<f64>()
+                + 50.0 * analyze(game, &expected_values, &projections).bonus_probability;
+            let expected = TurnTable::new(game, &expected_values).value(game.dice(), rerolls_left);
+            assert!(
+                (projected - expected).abs() < 1e-9,
+                "{game:?}: projected {projected}, expected {expected}"
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rustc_hash::FxBuildHasher;
+use yatzy::{Combo, Dice, Game};
+use yatzy_compute_expected_values::GameState;
+
+use yatzy_solver::{
+    best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls, choice_value,
+    turn::TurnTable,
+};
+
+use crate::simulate::game_rng;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct CrossCheckArgs {
+    #[arg(short = 'n', long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+    positions: u64,
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, default_value_t = 1e-9)]
+    tolerance: f64,
+}
+
+fn random_position<R: Rng>(rng: &mut R) -> Game {
+    let mut game = Game::new_random(rng);
+    let open = rng.random_range(0..15);
+    for (i, combo) in Combo::iter().enumerate() {
+        if i != open && rng.random_bool(0.5) {
+            game.set_combo_raw(combo, Some(combo.points(Dice::new_random(rng))));
+        }
+    }
+    game.set_rerolls(rng.random_range(0..=2));
+    game
+}
+
+// returns whether every position agreed
+pub fn cross_check(
+    args: CrossCheckArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> bool {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut generic_time = Duration::ZERO;
+    let mut table_time = Duration::ZERO;
+    let mut max_difference = 0_f64;
+    let mut mismatches = 0;
+
+    for i in 0..args.positions {
+        let game = random_position(&mut game_rng(seed, i));
+
+        let start = Instant::now();
+        let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        let (generic_choices, generic_value) = match game.rerolls_left() {
+            0 => best_choice_0_rerolls::<_, FxBuildHasher, _, f64>(game, expected_values, &cache),
+            1 => best_choice_1_reroll::<_, FxBuildHasher, _, f64>(game, expected_values, &cache),
+            2 => best_choice_2_rerolls::<_, FxBuildHasher, _, f64>(game, expected_values, &cache),
+            _ => unreachable!(),
+        };
+        generic_time += start.elapsed();
+
+        let start = Instant::now();
+        let (table_choices, table_value) =
+            TurnTable::new(game, expected_values).best_choices(game.dice(), game.rerolls_left());
+        table_time += start.elapsed();
+
+        let difference = (generic_value - table_value).abs();
+        max_difference = max_difference.max(difference);
+
+        // near ties may be broken differently by rounding, so a choice only
+        // counts as wrong if the generic solver values it below its best
+        let wrong_choices: Vec<_> = table_choices
+            .iter()
+            .filter(|&&choice| {
+                !generic_choices.contains(&choice)
+                    && generic_value
+                        - choice_value::<_, FxBuildHasher, _, f64>(
+                            game,
+                            choice,
+                            expected_values,
+                            &cache,
+                        )
+                        > args.tolerance
+            })
+            .collect();
+        if difference > args.tolerance {
+            mismatches += 1;
+            println!(
+                "position {i}: {game:?}: generic value {generic_value}, table value {table_value}"
+            );
+        } else if !wrong_choices.is_empty() {
+            mismatches += 1;
+            println!(
+                "position {i}: {game:?}: table chose {wrong_choices:?}, generic chose {generic_choices:?}"
+            );
+        }
+    }
+
+    let n = args.positions as f64;
+    println!(
+        "{} positions checked (seed {seed}), {mismatches} mismatches, largest value difference {max_difference:e}",
+        args.positions,
+    );
+    println!(
+        "average time per position: generic {:.3} ms, table {:.3} ms",
+        generic_time.as_secs_f64() * 1000.0 / n,
+        table_time.as_secs_f64() * 1000.0 / n,
+    );
+    mismatches == 0
+}
@@ -5,144 +5,19 @@ use std::{
     ops::{AddAssign, Mul},
 };
 
-use itertools::Itertools as _;
-use lazy_static::lazy_static;
-use num_bigint::BigUint;
-use num_rational::Ratio;
-use num_traits::ToPrimitive as _;
 use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
-use rustc_hash::FxBuildHasher;
 use yatzy::{Combo, Die, Game};
-use yatzy_compute_expected_values::{rational::prob, state_from_game};
-
-pub use yatzy_compute_expected_values::{Choice, GameState};
-
-pub trait Value: Sized {
-    fn from_u8(input: u8) -> Self;
-    fn from_u16(input: u16) -> Self;
-    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)>;
-    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)>;
-    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)>;
-    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)>;
-    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)>;
-    fn zero() -> Self;
-}
-
-impl Value for f64 {
-    fn from_u8(input: u8) -> Self {
-        input.into()
-    }
-
-    fn from_u16(input: u16) -> Self {
-        input.into()
-    }
-
-    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
-        &ROLL_1_PROB_FLOAT
-    }
-
-    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
-        &ROLL_2_PROB_FLOAT
-    }
-
-    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
-        &ROLL_3_PROB_FLOAT
-    }
-
-    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
-        &ROLL_4_PROB_FLOAT
-    }
-
-    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
-        &ROLL_5_PROB_FLOAT
-    }
-
-    fn zero() -> Self {
-        0.0
-    }
-}
-
-impl Value for Ratio<BigUint> {
-    fn from_u8(input: u8) -> Self {
-        Ratio::from(BigUint::from(input))
-    }
-
-    fn from_u16(input: u16) -> Self {
-        Ratio::from(BigUint::from(input))
-    }
-
-    fn roll_1_prob<'a>() -> &'a Vec<([Die; 1], Self)> {
-        &ROLL_1_PROB_RATIO
-    }
-
-    fn roll_2_prob<'a>() -> &'a Vec<([Die; 2], Self)> {
-        &ROLL_2_PROB_RATIO
-    }
-
-    fn roll_3_prob<'a>() -> &'a Vec<([Die; 3], Self)> {
-        &ROLL_3_PROB_RATIO
-    }
+use yatzy_compute_expected_values::state_from_game;
 
-    fn roll_4_prob<'a>() -> &'a Vec<([Die; 4], Self)> {
-        &ROLL_4_PROB_RATIO
-    }
+pub use yatzy_compute_expected_values::{Choice, GameState, Keep, Value, table};
 
-    fn roll_5_prob<'a>() -> &'a Vec<([Die; 5], Self)> {
-        &ROLL_5_PROB_RATIO
-    }
+pub mod analysis;
+pub mod noisy;
+pub mod strategy;
+pub mod turn;
 
-    fn zero() -> Self {
-        Self::default()
-    }
-}
-
-fn convert_prob_to_ratio(ratio: Ratio<u16>) -> Ratio<BigUint> {
-    let (numer, denom) = ratio.into_raw();
-    Ratio::new(numer.into(), denom.into())
-}
-
-lazy_static! {
-    static ref ROLL_1_PROB_RATIO: Vec<([Die; 1], Ratio<BigUint>)> = prob::ROLL_1_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
-        .collect();
-    static ref ROLL_2_PROB_RATIO: Vec<([Die; 2], Ratio<BigUint>)> = prob::ROLL_2_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
-        .collect();
-    static ref ROLL_3_PROB_RATIO: Vec<([Die; 3], Ratio<BigUint>)> = prob::ROLL_3_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
-        .collect();
-    static ref ROLL_4_PROB_RATIO: Vec<([Die; 4], Ratio<BigUint>)> = prob::ROLL_4_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
-        .collect();
-    static ref ROLL_5_PROB_RATIO: Vec<([Die; 5], Ratio<BigUint>)> = prob::ROLL_5_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, convert_prob_to_ratio(prob)))
-        .collect();
-    static ref ROLL_1_PROB_FLOAT: Vec<([Die; 1], f64)> = prob::ROLL_1_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
-        .collect();
-    static ref ROLL_2_PROB_FLOAT: Vec<([Die; 2], f64)> = prob::ROLL_2_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
-        .collect();
-    static ref ROLL_3_PROB_FLOAT: Vec<([Die; 3], f64)> = prob::ROLL_3_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
-        .collect();
-    static ref ROLL_4_PROB_FLOAT: Vec<([Die; 4], f64)> = prob::ROLL_4_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
-        .collect();
-    static ref ROLL_5_PROB_FLOAT: Vec<([Die; 5], f64)> = prob::ROLL_5_PROB
-        .into_iter()
-        .map(|(dice, prob)| (dice, prob.to_f64().unwrap()))
-        .collect();
-}
+pub type ChoiceCache<S2, S3, V> =
+    papaya::HashMap<(Game, Option<Choice>), (Option<HashSet<Choice, S2>>, V), S3>;
 
 fn expected_score<S, V>(game: Game, expected_values: &papaya::HashMap<GameState, V, S>) -> V
 where
@@ -226,27 +101,16 @@ where
         return (choices.clone(), value.clone());
     }
 
-    let mut choices = HashSet::with_hasher(FxBuildHasher);
+    let mut choices = Vec::new();
 
     for combo in Combo::iter() {
         if game.combo(combo).is_none() {
-            choices.insert(Choice::SelectCombo(combo));
+            choices.push(Choice::SelectCombo(combo));
         }
     }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll1(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll2(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll3(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll4(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll5(dice));
+    let hand = game.dice();
+    for keep in Keep::all(hand.as_slice()) {
+        choices.push(keep.reroll_choice(hand.as_slice()).unwrap());
     }
 
     let mut best_choices = Vec::new();
@@ -362,27 +226,16 @@ where
         return (choices.clone(), value.clone());
     }
 
-    let mut choices = HashSet::with_hasher(FxBuildHasher);
+    let mut choices = Vec::new();
 
     for combo in Combo::iter() {
         if game.combo(combo).is_none() {
-            choices.insert(Choice::SelectCombo(combo));
+            choices.push(Choice::SelectCombo(combo));
         }
     }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll1(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll2(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll3(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll4(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll5(dice));
+    let hand = game.dice();
+    for keep in Keep::all(hand.as_slice()) {
+        choices.push(keep.reroll_choice(hand.as_slice()).unwrap());
     }
 
     let mut best_choices = Vec::new();
@@ -486,27 +339,16 @@ where
 {
     assert!(game.rerolls_left() == 2);
 
-    let mut choices = HashSet::with_hasher(FxBuildHasher);
+    let mut choices = Vec::new();
 
     for combo in Combo::iter() {
         if game.combo(combo).is_none() {
-            choices.insert(Choice::SelectCombo(combo));
+            choices.push(Choice::SelectCombo(combo));
         }
     }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll1(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll2(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll3(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll4(dice));
-    }
-    for dice in game.dice().into_iter().array_combinations() {
-        choices.insert(Choice::Reroll5(dice));
+    let hand = game.dice();
+    for keep in Keep::all(hand.as_slice()) {
+        choices.push(keep.reroll_choice(hand.as_slice()).unwrap());
     }
 
     let mut best_choices = Vec::new();
@@ -588,3 +430,105 @@ where
         .collect();
     (best_choices, max_expected_value)
 }
+
+pub fn choice_value<S1, S2, S3, V>(
+    game: Game,
+    choice: Choice,
+    expected_values: &papaya::HashMap<GameState, V, S1>,
+    cache: &ChoiceCache<S2, S3, V>,
+) -> V
+where
+    S1: BuildHasher,
+    S2: BuildHasher + Clone + Default,
+    S3: BuildHasher,
+    V: Value + AddAssign + Clone + PartialOrd + for<'a> Sum<<&'a V as Mul<V>>::Output>,
+    for<'a> &'a V: Mul<V> + PartialEq<&'a V>,
+{
+    assert!(matches!(choice, Choice::SelectCombo(_)) || game.rerolls_left() > 0);
+
+    if let Some((None, value)) = cache.pin().get(&(game, Some(choice))) {
+        return value.clone();
+    }
+
+    let reroll_value = |dice: &[Die], new_dice: &[Die]| {
+        let mut game = game;
+        game.replace_dice(dice, new_dice).unwrap();
+        game.set_rerolls(game.rerolls_left() - 1);
+        match game.rerolls_left() {
+            0 => best_choice_0_rerolls(game, expected_values, cache).1,
+            1 => best_choice_1_reroll_non_parallel(game, expected_values, cache).1,
+            _ => unreachable!(),
+        }
+    };
+    let value = match choice {
+        Choice::SelectCombo(combo) => {
+            let mut game = game;
+            game.set_combo_raw(combo, Some(combo.points(game.dice())));
+            expected_score(game, expected_values)
+        }
+        Choice::Reroll1(dice) => V::roll_1_prob()
+            .iter()
+            .map(|(new_dice, prob)| prob * reroll_value(&dice, new_dice))
+            .sum(),
+        Choice::Reroll2(dice) => V::roll_2_prob()
+            .iter()
+            .map(|(new_dice, prob)| prob * reroll_value(&dice, new_dice))
+            .sum(),
+        Choice::Reroll3(dice) => V::roll_3_prob()
+            .iter()
+            .map(|(new_dice, prob)| prob * reroll_value(&dice, new_dice))
+            .sum(),
+        Choice::Reroll4(dice) => V::roll_4_prob()
+            .iter()
+            .map(|(new_dice, prob)| prob * reroll_value(&dice, new_dice))
+            .sum(),
+        Choice::Reroll5(dice) => V::roll_5_prob()
+            .iter()
+            .map(|(new_dice, prob)| prob * reroll_value(&dice, new_dice))
+            .sum(),
+    };
+    cache
+        .pin()
+        .insert((game, Some(choice)), (None, value.clone()));
+    value
+}
+
+pub fn legal_choices(game: Game) -> Vec<Choice> {
+    let mut choices: Vec<Choice> = Combo::iter()
+        .filter(|&combo| game.combo(combo).is_none())
+        .map(Choice::SelectCombo)
+        .collect();
+    if game.rerolls_left() > 0 {
+        let hand = game.dice();
+        for keep in Keep::all(hand.as_slice()) {
+            choices.push(keep.reroll_choice(hand.as_slice()).unwrap());
+        }
+    }
+    choices
+}
+
+pub fn evaluate_choices<S1, S2, S3, V>(
+    game: Game,
+    expected_values: &papaya::HashMap<GameState, V, S1>,
+    cache: &ChoiceCache<S2, S3, V>,
+) -> Vec<(Choice, V)>
+where
+    S1: BuildHasher + Sync,
+    S2: BuildHasher + Clone + Default + Send + Sync,
+    S3: BuildHasher + Sync,
+    V: Value
+        + AddAssign
+        + Clone
+        + PartialOrd
+        + for<'a> Sum<<&'a V as Mul<V>>::Output>
+        + Send
+        + Sync,
+    for<'a> &'a V: Mul<V> + PartialEq<&'a V>,
+{
+    assert!(!game.ended());
+
+    legal_choices(game)
+        .par_iter()
+        .map(|&choice| (choice, choice_value(game, choice, expected_values, cache)))
+        .collect()
+}
@@ -1,34 +1,71 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
+use clap::{Parser, Subcommand};
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
 use num_rational::Ratio;
 use num_traits::ToPrimitive as _;
 use rustc_hash::FxBuildHasher;
 use yatzy::{Combo, Game, print_game};
-use yatzy_compute_expected_values::{Choice, GameState};
+use yatzy_compute_expected_values::{Choice, GameState, table};
 
 use yatzy_solver::{best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls};
 
+use crate::{
+    cross_check::{CrossCheckArgs, cross_check},
+    project::{ProjectArgs, project},
+    query::{AdviseArgs, StateValueArgs, advise, state_value},
+    simulate::{SimulateArgs, simulate},
+    tournament::{TournamentArgs, tournament},
+};
+
+mod cross_check;
+mod project;
+mod query;
+mod simulate;
+mod tournament;
+
 lazy_static! {
     static ref EXPECTED_VALUES: papaya::HashMap<GameState, f64, FxBuildHasher> =
         papaya::HashMap::with_capacity_and_hasher(958_974, FxBuildHasher);
 }
 
+#[derive(Clone, Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    #[arg(short, long, default_value = "expected-values")]
+    expected_values: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Command {
+    Advise(AdviseArgs),
+    Benchmark,
+    CrossCheck(CrossCheckArgs),
+    Project(ProjectArgs),
+    Simulate(SimulateArgs),
+    StateValue(StateValueArgs),
+    Tournament(TournamentArgs),
+}
+
 fn main() {
+    let args = Args::parse();
     let expected_values = EXPECTED_VALUES.pin();
 
+    let path = args.expected_values.display();
     let map: HashMap<GameState, Ratio<BigUint>, FxBuildHasher> =
-        match std::fs::read("expected-values") {
-            Ok(bytes) => match postcard::from_bytes(&bytes) {
+        match std::fs::read(&args.expected_values) {
+            Ok(bytes) => match table::decode(&bytes) {
                 Ok(map) => map,
                 Err(error) => {
-                    eprintln!("failed to read `expected-values`: {error}");
+                    eprintln!("failed to read `{path}`: {error}");
                     std::process::exit(1);
                 }
             },
             Err(error) => {
-                eprintln!("failed to open `expected-values`: {error}");
+                eprintln!("failed to open `{path}`: {error}");
                 std::process::exit(1);
             }
         };
@@ -37,6 +74,51 @@ fn main() {
         expected_values.insert(k, v.to_f64().unwrap());
     }
 
+    match args.command {
+        Some(Command::Simulate(args)) => {
+            if let Err(error) = simulate(args, &EXPECTED_VALUES) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Tournament(args)) => {
+            if let Err(error) = tournament(args, &EXPECTED_VALUES) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Advise(args)) => {
+            if let Err(error) = advise(args, &EXPECTED_VALUES) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::StateValue(args)) => {
+            if let Err(error) = state_value(args, &EXPECTED_VALUES) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Project(args)) => {
+            if let Err(error) = project(args, &EXPECTED_VALUES) {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::CrossCheck(args)) => {
+            if !cross_check(args, &EXPECTED_VALUES) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Benchmark) | None => {}
+    }
+
     let mut total = 0_u64;
     let n = 10_000;
     for i in 1..=n {
@@ -0,0 +1,104 @@
+use std::hash::BuildHasher;
+
+use rand::{Rng, seq::IndexedRandom as _};
+use rustc_hash::FxBuildHasher;
+use yatzy::Game;
+
+use crate::{Choice, GameState, evaluate_choices, strategy::Strategy};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Noise {
+    Softmax { temperature: f64 },
+    BoundedLoss { max_loss: f64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum NoiseError {
+    #[error("temperature must be positive and finite")]
+    InvalidTemperature,
+    #[error("maximum loss must be non-negative and finite")]
+    InvalidMaxLoss,
+}
+
+impl Noise {
+    pub fn softmax(temperature: f64) -> Result<Self, NoiseError> {
+        if !(temperature.is_finite() && temperature > 0.0) {
+            return Err(NoiseError::InvalidTemperature);
+        }
+        Ok(Self::Softmax { temperature })
+    }
+
+    pub fn bounded_loss(max_loss: f64) -> Result<Self, NoiseError> {
+        if !(max_loss.is_finite() && max_loss >= 0.0) {
+            return Err(NoiseError::InvalidMaxLoss);
+        }
+        Ok(Self::BoundedLoss { max_loss })
+    }
+}
+
+pub fn sample_choice<R: Rng>(evaluations: &[(Choice, f64)], noise: Noise, rng: &mut R) -> Choice {
+    let best = evaluations
+        .iter()
+        .map(|&(_, value)| value)
+        .fold(f64::NEG_INFINITY, f64::max);
+    match noise {
+        Noise::Softmax { temperature } => {
+            let weights: Vec<f64> = evaluations
+                .iter()
+                .map(|&(_, value)| ((value - best) / temperature).exp())
+                .collect();
+            let mut target = rng.random::<f64>() * weights.iter().sum::<f64>();
+            for (&(choice, _), weight) in evaluations.iter().zip(weights) {
+                if target < weight {
+                    return choice;
+                }
+                target -= weight;
+            }
+            evaluations.last().expect("no choices").0
+        }
+        Noise::BoundedLoss { max_loss } => {
+            let candidates: Vec<Choice> = evaluations
+                .iter()
+                .filter(|&&(_, value)| best - value <= max_loss)
+                .map(|&(choice, _)| choice)
+                .collect();
+            *candidates.choose(rng).expect("no choices")
+        }
+    }
+}
+
+pub struct Noisy<'a, S, R> {
+    expected_values: &'a papaya::HashMap<GameState, f64, S>,
+    noise: Noise,
+    rng: R,
+}
+
+impl<'a, S, R> Noisy<'a, S, R> {
+    pub fn new(
+        expected_values: &'a papaya::HashMap<GameState, f64, S>,
+        noise: Noise,
+        rng: R,
+    ) -> Self {
+        Self {
+            expected_values,
+            noise,
+            rng,
+        }
+    }
+}
+
+impl<S: BuildHasher + Sync, R: Rng> Strategy for Noisy<'_, S, R> {
+    fn name(&self) -> &'static str {
+        match self.noise {
+            Noise::Softmax { .. } => "softmax",
+            Noise::BoundedLoss { .. } => "bounded-loss",
+        }
+    }
+
+    fn choose(&mut self, game: &Game) -> Choice {
+        let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        let evaluations =
+            evaluate_choices::<_, FxBuildHasher, _, f64>(*game, self.expected_values, &cache);
+        sample_choice(&evaluations, self.noise, &mut self.rng)
+    }
+}
@@ -0,0 +1,46 @@
+use std::{io, path::PathBuf};
+
+use rustc_hash::FxBuildHasher;
+use yatzy_compute_expected_values::{GameState, game_states_by_empty_field_count};
+
+use yatzy_solver::analysis::{Projections, compute_projections, encode_projections};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct ProjectArgs {
+    #[arg(short, long, default_value = "projections")]
+    output: PathBuf,
+}
+
+pub fn project(
+    args: ProjectArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> io::Result<()> {
+    let states = game_states_by_empty_field_count();
+    let mut projections: Projections<FxBuildHasher> =
+        Projections::with_capacity_and_hasher(expected_values.len(), FxBuildHasher);
+    for n in 1..=15 {
+        let states = states.get(&n).unwrap();
+        eprintln!(
+            "calculating projections for game states with {} empty field(s) ({} states)",
+            n,
+            states.len(),
+        );
+        let layer = compute_projections(states, expected_values, &projections);
+        projections.extend(layer);
+    }
+
+    let initial_value = *expected_values.pin().get(&GameState::initial()).unwrap();
+    let bytes = encode_projections(initial_value, &projections).map_err(io::Error::other)?;
+    // write next to the target first so that an interrupted write never
+    // leaves a truncated file behind
+    let mut temporary = args.output.as_os_str().to_owned();
+    temporary.push(".tmp");
+    std::fs::write(&temporary, bytes)?;
+    std::fs::rename(&temporary, &args.output)?;
+    eprintln!(
+        "{} projections written to {}",
+        projections.len(),
+        args.output.display(),
+    );
+    Ok(())
+}
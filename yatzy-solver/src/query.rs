@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+
+use rustc_hash::FxBuildHasher;
+use serde::Serialize;
+use yatzy::{Combo, Die, Game, GameOptions, NewGameError, ParseComboError};
+use yatzy_compute_expected_values::{FieldState, GameState, state_from_game};
+
+use yatzy_solver::{Choice, evaluate_choices};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct AdviseArgs {
+    #[arg(long)]
+    dice: String,
+    #[arg(long, default_value_t = 2)]
+    rerolls: u8,
+    #[arg(long, default_value = "")]
+    filled: String,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct StateValueArgs {
+    #[arg(default_value = "")]
+    state: String,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("invalid dice `{0}`")]
+    InvalidDice(String),
+    #[error("invalid filled combo `{0}`")]
+    InvalidFilled(String),
+    #[error("{0}")]
+    UnknownCombo(#[from] ParseComboError),
+    #[error("combo {0} is given more than once")]
+    DuplicateCombo(Combo),
+    #[error("{0}")]
+    InvalidGame(#[from] NewGameError),
+    #[error("the game has ended")]
+    GameEnded,
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct RankedChoice {
+    choice: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    combo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dice: Option<Vec<Die>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep: Option<Vec<Die>>,
+    value: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Advice {
+    state: GameState,
+    dice: Vec<Die>,
+    rerolls_left: u8,
+    value: f64,
+    choices: Vec<RankedChoice>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct StateValue {
+    state: GameState,
+    scored: u16,
+    remaining_value: f64,
+    value: f64,
+}
+
+fn parse_dice(input: &str) -> Result<[Die; 5], QueryError> {
+    let dice: Vec<Die> = input
+        .split(',')
+        .map(|die| die.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| QueryError::InvalidDice(String::from(input)))?;
+    dice.try_into()
+        .map_err(|_| QueryError::InvalidDice(String::from(input)))
+}
+
+// `ones=3,yatzy=0` lists the filled combos and the points scored in them
+fn parse_game(dice: [Die; 5], rerolls_left: u8, filled: &str) -> Result<Game, QueryError> {
+    let mut points = [None; 15];
+    for entry in filled.split(',') {
+        if entry.trim().is_empty() {
+            continue;
+        }
+        let Some((combo, value)) = entry.split_once('=') else {
+            return Err(QueryError::InvalidFilled(String::from(entry)));
+        };
+        let combo: Combo = combo.parse()?;
+        let Ok(value) = value.trim().parse::<u8>() else {
+            return Err(QueryError::InvalidFilled(String::from(entry)));
+        };
+        let index = Combo::iter().position(|c| c == combo).unwrap();
+        if points[index].is_some() {
+            return Err(QueryError::DuplicateCombo(combo));
+        }
+        points[index] = Some(value);
+    }
+    let [
+        ones,
+        twos,
+        threes,
+        fours,
+        fives,
+        sixes,
+        one_pair,
+        two_pairs,
+        three_of_a_kind,
+        four_of_a_kind,
+        small_straight,
+        large_straight,
+        full_house,
+        chance,
+        yatzy,
+    ] = points;
+    Ok(Game::new(GameOptions {
+        dice,
+        rerolls_left,
+        ones,
+        twos,
+        threes,
+        fours,
+        fives,
+        sixes,
+        one_pair,
+        two_pairs,
+        three_of_a_kind,
+        four_of_a_kind,
+        small_straight,
+        large_straight,
+        full_house,
+        chance,
+        yatzy,
+    })?)
+}
+
+fn scored(game: Game) -> u16 {
+    Combo::iter()
+        .map(|combo| u16::from(game.combo(combo).unwrap_or(0)))
+        .sum()
+}
+
+fn describe(choice: &RankedChoice) -> String {
+    match (&choice.combo, &choice.dice, &choice.keep) {
+        (Some(combo), _, _) => format!("select {combo}"),
+        (None, Some(dice), Some(keep)) => format!(
+            "reroll {} (keep {})",
+            dice.iter()
+                .map(Die::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            if keep.is_empty() {
+                String::from("nothing")
+            } else {
+                keep.iter()
+                    .map(Die::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            },
+        ),
+        _ => String::from(choice.choice),
+    }
+}
+
+fn print_state(state: GameState) {
+    let empty: Vec<String> = Combo::iter()
+        .filter(|&combo| state.field(combo) == FieldState::Empty)
+        .map(|combo| combo.to_string())
+        .collect();
+    println!(
+        "state: numbers_total {}, empty: {}",
+        state.numbers_total,
+        if empty.is_empty() {
+            String::from("none")
+        } else {
+            empty.join(", ")
+        },
+    );
+}
+
+pub fn advise(
+    args: AdviseArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> Result<(), QueryError> {
+    let game = parse_game(parse_dice(&args.dice)?, args.rerolls, &args.filled)?;
+    if game.ended() {
+        return Err(QueryError::GameEnded);
+    }
+
+    let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+    let mut values = evaluate_choices::<_, FxBuildHasher, _, f64>(game, expected_values, &cache);
+    values.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+    let hand = game.dice();
+    let choices: Vec<RankedChoice> = values
+        .into_iter()
+        .map(|(choice, value)| match choice {
+            Choice::SelectCombo(combo) => RankedChoice {
+                choice: "select_combo",
+                combo: Some(combo.to_string()),
+                dice: None,
+                keep: None,
+                value,
+            },
+            Choice::Reroll1(_)
+            | Choice::Reroll2(_)
+            | Choice::Reroll3(_)
+            | Choice::Reroll4(_)
+            | Choice::Reroll5(_) => RankedChoice {
+                choice: "reroll",
+                combo: None,
+                dice: Some(choice.rerolled_dice().unwrap().to_vec()),
+                keep: Some(choice.keep(hand.as_slice()).unwrap().dice()),
+                value,
+            },
+        })
+        .collect();
+    let advice = Advice {
+        state: state_from_game(game),
+        dice: hand.as_slice().to_vec(),
+        rerolls_left: game.rerolls_left(),
+        value: choices[0].value,
+        choices,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&advice)?);
+        return Ok(());
+    }
+    print_state(advice.state);
+    println!(
+        "dice: {}, rerolls left: {}",
+        advice
+            .dice
+            .iter()
+            .map(Die::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        advice.rerolls_left,
+    );
+    println!("expected final score: {:.4}", advice.value);
+    for (i, choice) in advice.choices.iter().enumerate() {
+        println!(
+            "{:>3}. {:<32} {:>9.4} {:>+9.4}",
+            i + 1,
+            describe(choice),
+            choice.value,
+            choice.value - advice.value,
+        );
+    }
+    Ok(())
+}
+
+pub fn state_value(
+    args: StateValueArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> Result<(), QueryError> {
+    // the dice do not affect the value at the start of a turn
+    let game = parse_game([1, 1, 1, 1, 1], 2, &args.state)?;
+    let state = state_from_game(game);
+    let scored = scored(game);
+    let remaining_value = if game.ended() {
+        f64::from(game.score() - scored)
+    } else {
+        *expected_values.pin().get(&state).unwrap()
+    };
+    let value = StateValue {
+        state,
+        scored,
+        remaining_value,
+        value: f64::from(scored) + remaining_value,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+    print_state(value.state);
+    println!("scored: {}", value.scored);
+    println!("expected remaining points: {:.4}", value.remaining_value);
+    println!("expected final score: {:.4}", value.value);
+    Ok(())
+}
@@ -0,0 +1,321 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use rand::SeedableRng as _;
+use rand_chacha::ChaCha8Rng;
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+use rustc_hash::FxBuildHasher;
+use serde::Serialize;
+use yatzy::Combo;
+use yatzy_compute_expected_values::GameState;
+
+use yatzy_solver::strategy::{Greedy, Optimal, Random, Strategy, UpperSectionFirst, play};
+
+const PERCENTILES: [u8; 9] = [1, 5, 10, 25, 50, 75, 90, 95, 99];
+pub const Z_95: f64 = 1.959_963_984_540_054;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StrategyKind {
+    #[default]
+    Optimal,
+    Greedy,
+    Random,
+    UpperSectionFirst,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct SimulateArgs {
+    #[arg(short, long, value_enum, default_value_t)]
+    strategy: StrategyKind,
+    #[arg(short = 'n', long, default_value_t = 10_000, value_parser = clap::value_parser!(u64).range(1..))]
+    games: u64,
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..))]
+    bin_width: u16,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct GameResult {
+    score: u16,
+    bonus: bool,
+    points: [u8; 15],
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Summary {
+    strategy: String,
+    games: u64,
+    seed: u64,
+    mean: f64,
+    standard_deviation: f64,
+    confidence_interval_95: (f64, f64),
+    min: u16,
+    max: u16,
+    percentiles: Vec<Percentile>,
+    histogram: Vec<Bin>,
+    bonus_rate: f64,
+    combos: Vec<ComboSummary>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct Percentile {
+    percentile: u8,
+    score: u16,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct Bin {
+    from: u16,
+    to: u16,
+    count: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ComboSummary {
+    combo: String,
+    fill_rate: f64,
+    average_points: f64,
+}
+
+impl StrategyKind {
+    pub fn build<'a>(
+        self,
+        expected_values: &'a papaya::HashMap<GameState, f64, FxBuildHasher>,
+        rng: ChaCha8Rng,
+    ) -> Box<dyn Strategy + 'a> {
+        match self {
+            Self::Optimal => Box::new(Optimal::new(expected_values)),
+            Self::Greedy => Box::new(Greedy),
+            Self::Random => Box::new(Random::new(rng)),
+            Self::UpperSectionFirst => Box::new(UpperSectionFirst),
+        }
+    }
+}
+
+pub fn game_rng(seed: u64, game: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(game);
+    rng
+}
+
+fn percentile(sorted: &[u16], percentile: u8) -> u16 {
+    let rank = (f64::from(percentile) / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn summarize(results: &[GameResult], strategy: &str, seed: u64, bin_width: u16) -> Summary {
+    let n = results.len() as f64;
+    let mut scores: Vec<u16> = results.iter().map(|result| result.score).collect();
+    scores.sort_unstable();
+
+    let mean = scores.iter().map(|&score| f64::from(score)).sum::<f64>() / n;
+    let variance = scores
+        .iter()
+        .map(|&score| (f64::from(score) - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0).max(1.0);
+    let standard_deviation = variance.sqrt();
+    let margin = Z_95 * standard_deviation / n.sqrt();
+
+    let min = scores[0];
+    let max = scores[scores.len() - 1];
+    let mut histogram = Vec::new();
+    let mut from = min / bin_width * bin_width;
+    while from <= max {
+        let to = from + bin_width - 1;
+        let count = scores
+            .iter()
+            .filter(|&&score| (from..=to).contains(&score))
+            .count() as u64;
+        histogram.push(Bin { from, to, count });
+        from += bin_width;
+    }
+
+    let combos = Combo::iter()
+        .enumerate()
+        .map(|(index, combo)| ComboSummary {
+            combo: combo.to_string(),
+            fill_rate: results
+                .iter()
+                .filter(|result| result.points[index] > 0)
+                .count() as f64
+                / n,
+            average_points: results
+                .iter()
+                .map(|result| f64::from(result.points[index]))
+                .sum::<f64>()
+                / n,
+        })
+        .collect();
+
+    Summary {
+        strategy: String::from(strategy),
+        games: results.len() as u64,
+        seed,
+        mean,
+        standard_deviation,
+        confidence_interval_95: (mean - margin, mean + margin),
+        min,
+        max,
+        percentiles: PERCENTILES
+            .iter()
+            .map(|&p| Percentile {
+                percentile: p,
+                score: percentile(&scores, p),
+            })
+            .collect(),
+        histogram,
+        bonus_rate: results.iter().filter(|result| result.bonus).count() as f64 / n,
+        combos,
+    }
+}
+
+fn write_text(summary: &Summary, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "{}: {} games (seed {})",
+        summary.strategy, summary.games, summary.seed,
+    )?;
+    writeln!(
+        out,
+        "mean: {:.3} ± {:.3} (95% CI {:.3}–{:.3})",
+        summary.mean,
+        summary.confidence_interval_95.1 - summary.mean,
+        summary.confidence_interval_95.0,
+        summary.confidence_interval_95.1,
+    )?;
+    writeln!(out, "standard deviation: {:.3}", summary.standard_deviation)?;
+    writeln!(out, "min: {}, max: {}", summary.min, summary.max)?;
+    for p in &summary.percentiles {
+        writeln!(out, "p{}: {}", p.percentile, p.score)?;
+    }
+    writeln!(out, "bonus rate: {:.2}%", summary.bonus_rate * 100.0)?;
+    writeln!(out)?;
+
+    let largest = summary
+        .histogram
+        .iter()
+        .map(|bin| bin.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for bin in &summary.histogram {
+        let bar = "#".repeat((bin.count * 50 / largest) as usize);
+        writeln!(out, "{:>3}–{:<3} {:>7} {bar}", bin.from, bin.to, bin.count)?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "{:<16} {:>9} {:>8}", "combo", "fill rate", "average")?;
+    for combo in &summary.combos {
+        writeln!(
+            out,
+            "{:<16} {:>8.2}% {:>8.3}",
+            combo.combo,
+            combo.fill_rate * 100.0,
+            combo.average_points,
+        )?;
+    }
+    Ok(())
+}
+
+fn write_csv(summary: &Summary, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "section,name,value")?;
+    writeln!(out, "summary,strategy,{}", summary.strategy)?;
+    writeln!(out, "summary,games,{}", summary.games)?;
+    writeln!(out, "summary,seed,{}", summary.seed)?;
+    writeln!(out, "summary,mean,{}", summary.mean)?;
+    writeln!(
+        out,
+        "summary,standard_deviation,{}",
+        summary.standard_deviation
+    )?;
+    writeln!(
+        out,
+        "summary,confidence_interval_95_low,{}",
+        summary.confidence_interval_95.0
+    )?;
+    writeln!(
+        out,
+        "summary,confidence_interval_95_high,{}",
+        summary.confidence_interval_95.1
+    )?;
+    writeln!(out, "summary,min,{}", summary.min)?;
+    writeln!(out, "summary,max,{}", summary.max)?;
+    writeln!(out, "summary,bonus_rate,{}", summary.bonus_rate)?;
+    for p in &summary.percentiles {
+        writeln!(out, "percentile,p{},{}", p.percentile, p.score)?;
+    }
+    for bin in &summary.histogram {
+        writeln!(out, "histogram,{}-{},{}", bin.from, bin.to, bin.count)?;
+    }
+    for combo in &summary.combos {
+        writeln!(out, "fill_rate,{},{}", combo.combo, combo.fill_rate)?;
+        writeln!(
+            out,
+            "average_points,{},{}",
+            combo.combo, combo.average_points
+        )?;
+    }
+    Ok(())
+}
+
+pub fn open_output(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(io::BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    })
+}
+
+pub fn simulate(
+    args: SimulateArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> io::Result<()> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    let results: Vec<GameResult> = (0..args.games)
+        .into_par_iter()
+        .map(|i| {
+            let mut strategy = args.strategy.build(expected_values, game_rng(!seed, i));
+            let game = play(strategy.as_mut(), &mut game_rng(seed, i));
+            let mut points = [0; 15];
+            for (index, combo) in Combo::iter().enumerate() {
+                points[index] = game.combo(combo).unwrap();
+            }
+            GameResult {
+                score: game.score(),
+                bonus: game.has_bonus(),
+                points,
+            }
+        })
+        .collect();
+    let strategy = args.strategy.build(expected_values, game_rng(!seed, 0));
+    let summary = summarize(&results, strategy.name(), seed, args.bin_width);
+
+    let mut out = open_output(args.output.as_deref())?;
+    match args.format {
+        Format::Text => write_text(&summary, &mut out)?,
+        Format::Csv => write_csv(&summary, &mut out)?,
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &summary)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()
+}
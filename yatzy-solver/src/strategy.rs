@@ -0,0 +1,150 @@
+use std::hash::BuildHasher;
+
+use rand::{Rng, seq::IndexedRandom as _};
+use rustc_hash::FxBuildHasher;
+use yatzy::{Combo, Die, Game};
+
+use crate::{
+    Choice, GameState, best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls,
+    legal_choices,
+};
+
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn choose(&mut self, game: &Game) -> Choice;
+}
+
+pub fn apply_choice<R: Rng>(game: &mut Game, choice: Choice, rng: &mut R) {
+    match choice {
+        Choice::SelectCombo(combo) => game.select_combo(combo, rng).unwrap(),
+        Choice::Reroll1(dice) => game.reroll(&dice, rng).unwrap(),
+        Choice::Reroll2(dice) => game.reroll(&dice, rng).unwrap(),
+        Choice::Reroll3(dice) => game.reroll(&dice, rng).unwrap(),
+        Choice::Reroll4(dice) => game.reroll(&dice, rng).unwrap(),
+        Choice::Reroll5(dice) => game.reroll(&dice, rng).unwrap(),
+    }
+}
+
+pub fn play<R: Rng>(strategy: &mut dyn Strategy, rng: &mut R) -> Game {
+    let mut game = Game::new_random(rng);
+    while !game.ended() {
+        let choice = strategy.choose(&game);
+        apply_choice(&mut game, choice, rng);
+    }
+    game
+}
+
+fn best_immediate_combo(game: &Game) -> Combo {
+    let mut best = None;
+    for combo in Combo::iter() {
+        if game.combo(combo).is_some() {
+            continue;
+        }
+        let points = combo.points(game.dice());
+        match best {
+            Some((_, best_points)) if best_points >= points => {}
+            _ => best = Some((combo, points)),
+        }
+    }
+    best.expect("game ended").0
+}
+
+pub struct Optimal<'a, S> {
+    expected_values: &'a papaya::HashMap<GameState, f64, S>,
+}
+
+impl<'a, S> Optimal<'a, S> {
+    pub fn new(expected_values: &'a papaya::HashMap<GameState, f64, S>) -> Self {
+        Self { expected_values }
+    }
+}
+
+impl<S: BuildHasher + Sync> Strategy for Optimal<'_, S> {
+    fn name(&self) -> &'static str {
+        "optimal"
+    }
+
+    fn choose(&mut self, game: &Game) -> Choice {
+        let game = *game;
+        let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        let (choices, _) = match game.rerolls_left() {
+            0 => best_choice_0_rerolls::<_, FxBuildHasher, _, f64>(
+                game,
+                self.expected_values,
+                &cache,
+            ),
+            1 => {
+                best_choice_1_reroll::<_, FxBuildHasher, _, f64>(game, self.expected_values, &cache)
+            }
+            2 => best_choice_2_rerolls::<_, FxBuildHasher, _, f64>(
+                game,
+                self.expected_values,
+                &cache,
+            ),
+            _ => unreachable!(),
+        };
+        choices.into_iter().next().unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn choose(&mut self, game: &Game) -> Choice {
+        Choice::SelectCombo(best_immediate_combo(game))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Random<R> {
+    rng: R,
+}
+
+impl<R> Random<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+impl<R: Rng> Strategy for Random<R> {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose(&mut self, game: &Game) -> Choice {
+        let choices = legal_choices(*game);
+        *choices.choose(&mut self.rng).expect("game ended")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UpperSectionFirst;
+
+impl Strategy for UpperSectionFirst {
+    fn name(&self) -> &'static str {
+        "upper-section-first"
+    }
+
+    fn choose(&mut self, game: &Game) -> Choice {
+        let dice = game.dice();
+        let target = Combo::iter()
+            .take(6)
+            .zip(1..=6)
+            .filter(|&(combo, _)| game.combo(combo).is_none())
+            .max_by_key(|&(_, face)| (dice.iter().filter(|&&die| die == face).count(), face));
+        let Some((combo, face)) = target else {
+            return Choice::SelectCombo(best_immediate_combo(game));
+        };
+
+        let reroll: Vec<Die> = dice.iter().copied().filter(|&die| die != face).collect();
+        if game.rerolls_left() > 0 && !reroll.is_empty() {
+            return Choice::reroll(&reroll).unwrap();
+        }
+        Choice::SelectCombo(combo)
+    }
+}
@@ -0,0 +1,293 @@
+use std::io::{self, Write};
+
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+use rustc_hash::FxBuildHasher;
+use serde::Serialize;
+use yatzy_compute_expected_values::GameState;
+
+use yatzy_solver::strategy::play;
+
+use crate::simulate::{Format, StrategyKind, Z_95, game_rng, open_output};
+
+const INITIAL_ELO: f64 = 1500.0;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct TournamentArgs {
+    #[arg(
+        short,
+        long = "strategy",
+        value_enum,
+        value_delimiter = ',',
+        required = true
+    )]
+    strategies: Vec<StrategyKind>,
+    #[arg(short = 'n', long, default_value_t = 1_000, value_parser = clap::value_parser!(u64).range(1..))]
+    games: u64,
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    #[arg(short, long)]
+    output: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TournamentError {
+    #[error("a tournament needs at least two strategies")]
+    TooFewStrategies,
+    #[error("strategy `{0}` given more than once")]
+    DuplicateStrategy(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Results {
+    games: u64,
+    seed: u64,
+    standings: Vec<Standing>,
+    matches: Vec<Match>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Standing {
+    strategy: String,
+    mean_score: f64,
+    win_rate: f64,
+    elo: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Match {
+    strategy_1: String,
+    strategy_2: String,
+    wins_1: u64,
+    wins_2: u64,
+    draws: u64,
+    win_rate_1: f64,
+    mean_difference: f64,
+    confidence_interval_95: (f64, f64),
+}
+
+fn compare(scores: &[Vec<u16>], i: usize, j: usize) -> (u64, u64, u64, f64, f64) {
+    let mut wins = (0, 0, 0);
+    let mut differences = Vec::with_capacity(scores.len());
+    for game in scores {
+        match game[i].cmp(&game[j]) {
+            std::cmp::Ordering::Greater => wins.0 += 1,
+            std::cmp::Ordering::Less => wins.1 += 1,
+            std::cmp::Ordering::Equal => wins.2 += 1,
+        }
+        differences.push(f64::from(game[i]) - f64::from(game[j]));
+    }
+    let n = differences.len() as f64;
+    let mean = differences.iter().sum::<f64>() / n;
+    let variance = differences
+        .iter()
+        .map(|difference| (difference - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0).max(1.0);
+    (
+        wins.0,
+        wins.1,
+        wins.2,
+        mean,
+        Z_95 * variance.sqrt() / n.sqrt(),
+    )
+}
+
+// Bradley-Terry ratings fitted with the MM algorithm, with one virtual draw
+// per pairing so that a strategy that never wins still gets a finite rating
+fn elo_ratings(points: &[Vec<f64>], games: f64) -> Vec<f64> {
+    let n = points.len();
+    let mut strengths = vec![1.0; n];
+    for _ in 0..1_000 {
+        let mut next = vec![0.0; n];
+        for i in 0..n {
+            let mut wins = 0.0;
+            let mut denominator = 0.0;
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                wins += points[i][j] + 0.5;
+                denominator += (games + 1.0) / (strengths[i] + strengths[j]);
+            }
+            next[i] = wins / denominator;
+        }
+        let log_mean = next.iter().map(|strength: &f64| strength.ln()).sum::<f64>() / n as f64;
+        for strength in &mut next {
+            *strength /= log_mean.exp();
+        }
+        let change = next
+            .iter()
+            .zip(&strengths)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        strengths = next;
+        if change < 1e-12 {
+            break;
+        }
+    }
+    strengths
+        .into_iter()
+        .map(|strength| INITIAL_ELO + 400.0 * strength.log10())
+        .collect()
+}
+
+fn write_text(results: &Results, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "{} games per match (seed {})",
+        results.games, results.seed
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<20} {:>10} {:>9} {:>8}",
+        "strategy", "mean score", "win rate", "elo"
+    )?;
+    for standing in &results.standings {
+        writeln!(
+            out,
+            "{:<20} {:>10.3} {:>8.2}% {:>8.1}",
+            standing.strategy,
+            standing.mean_score,
+            standing.win_rate * 100.0,
+            standing.elo,
+        )?;
+    }
+    writeln!(out)?;
+    for m in &results.matches {
+        writeln!(
+            out,
+            "{} vs {}: {}–{}–{} ({:.2}% wins), difference {:+.3} (95% CI {:+.3} to {:+.3})",
+            m.strategy_1,
+            m.strategy_2,
+            m.wins_1,
+            m.draws,
+            m.wins_2,
+            m.win_rate_1 * 100.0,
+            m.mean_difference,
+            m.confidence_interval_95.0,
+            m.confidence_interval_95.1,
+        )?;
+    }
+    Ok(())
+}
+
+fn write_csv(results: &Results, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "section,strategy_1,strategy_2,mean_score,win_rate,elo,wins_1,wins_2,draws,mean_difference,confidence_interval_95_low,confidence_interval_95_high"
+    )?;
+    for standing in &results.standings {
+        writeln!(
+            out,
+            "standing,{},,{},{},{},,,,,,",
+            standing.strategy, standing.mean_score, standing.win_rate, standing.elo,
+        )?;
+    }
+    for m in &results.matches {
+        writeln!(
+            out,
+            "match,{},{},,{},,{},{},{},{},{},{}",
+            m.strategy_1,
+            m.strategy_2,
+            m.win_rate_1,
+            m.wins_1,
+            m.wins_2,
+            m.draws,
+            m.mean_difference,
+            m.confidence_interval_95.0,
+            m.confidence_interval_95.1,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn tournament(
+    args: TournamentArgs,
+    expected_values: &papaya::HashMap<GameState, f64, FxBuildHasher>,
+) -> Result<(), TournamentError> {
+    if args.strategies.len() < 2 {
+        return Err(TournamentError::TooFewStrategies);
+    }
+    let names: Vec<&str> = args
+        .strategies
+        .iter()
+        .map(|kind| kind.build(expected_values, game_rng(0, 0)).name())
+        .collect();
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            return Err(TournamentError::DuplicateStrategy(String::from(*name)));
+        }
+    }
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    // every strategy plays game i with the same dice stream, so each score
+    // only has to be computed once and is reused for every pairing
+    let scores: Vec<Vec<u16>> = (0..args.games)
+        .into_par_iter()
+        .map(|i| {
+            args.strategies
+                .iter()
+                .map(|kind| {
+                    let mut strategy = kind.build(expected_values, game_rng(!seed, i));
+                    play(strategy.as_mut(), &mut game_rng(seed, i)).score()
+                })
+                .collect()
+        })
+        .collect();
+
+    let n = names.len();
+    let games = args.games as f64;
+    let mut points = vec![vec![0.0; n]; n];
+    let mut matches = Vec::new();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let (wins_1, wins_2, draws, mean, margin) = compare(&scores, i, j);
+            points[i][j] = wins_1 as f64 + draws as f64 / 2.0;
+            points[j][i] = wins_2 as f64 + draws as f64 / 2.0;
+            matches.push(Match {
+                strategy_1: String::from(names[i]),
+                strategy_2: String::from(names[j]),
+                wins_1,
+                wins_2,
+                draws,
+                win_rate_1: points[i][j] / games,
+                mean_difference: mean,
+                confidence_interval_95: (mean - margin, mean + margin),
+            });
+        }
+    }
+
+    let elo = elo_ratings(&points, games);
+    let mut standings: Vec<Standing> = (0..n)
+        .map(|i| Standing {
+            strategy: String::from(names[i]),
+            mean_score: scores.iter().map(|game| f64::from(game[i])).sum::<f64>() / games,
+            win_rate: points[i].iter().sum::<f64>() / (games * (n - 1) as f64),
+            elo: elo[i],
+        })
+        .collect();
+    standings.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+
+    let results = Results {
+        games: args.games,
+        seed,
+        standings,
+        matches,
+    };
+    let mut out = open_output(args.output.as_deref())?;
+    match args.format {
+        Format::Text => write_text(&results, &mut out)?,
+        Format::Csv => write_csv(&results, &mut out)?,
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, &results).map_err(io::Error::from)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
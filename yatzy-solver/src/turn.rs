@@ -0,0 +1,389 @@
+use std::{hash::BuildHasher, ops::AddAssign};
+
+use lazy_static::lazy_static;
+use num_traits::ToPrimitive as _;
+use yatzy::{
+    Combo, Dice, Game,
+    transitions::{self, DICE_COUNT, KEEP_COUNT},
+};
+
+use crate::{Choice, GameState, Keep, Value, expected_score};
+
+// transitions and sub-keeps flattened into contiguous arrays, indexed through
+// offset tables so that the inner loops only touch plain slices
+struct FlatTables {
+    transition_offsets: Vec<usize>,
+    outcomes: Vec<u8>,
+    probabilities: Vec<f64>,
+    keep_offsets: Vec<usize>,
+    keeps: Vec<u16>,
+}
+
+lazy_static! {
+    static ref TABLES: FlatTables = {
+        let mut transition_offsets = Vec::with_capacity(KEEP_COUNT + 1);
+        let mut outcomes = Vec::new();
+        let mut probabilities = Vec::new();
+        transition_offsets.push(0);
+        for keep in 0..KEEP_COUNT {
+            for &(outcome, probability) in transitions::transitions(keep) {
+                outcomes.push(outcome as u8);
+                probabilities.push(probability.to_f64().unwrap());
+            }
+            transition_offsets.push(outcomes.len());
+        }
+
+        let mut keep_offsets = Vec::with_capacity(DICE_COUNT + 1);
+        let mut keeps = Vec::new();
+        keep_offsets.push(0);
+        for dice in 0..DICE_COUNT {
+            // keeping the whole hand is not a reroll
+            for &keep in transitions::keeps(dice) {
+                if transitions::keep_len(keep) < 5 {
+                    keeps.push(keep as u16);
+                }
+            }
+            keep_offsets.push(keeps.len());
+        }
+
+        FlatTables {
+            transition_offsets,
+            outcomes,
+            probabilities,
+            keep_offsets,
+            keeps,
+        }
+    };
+}
+
+#[derive(Clone, Debug)]
+pub struct TurnTable {
+    combos: Vec<Combo>,
+    // combo_values[c * DICE_COUNT + dice]: expected final score of scoring combos[c]
+    combo_values: Vec<f64>,
+    // values[r][dice]: expected final score with r rerolls left
+    values: [Vec<f64>; 3],
+    // keep_values[r][keep]: expected final score of keeping dice with r rerolls left after rolling
+    keep_values: [Vec<f64>; 2],
+}
+
+impl TurnTable {
+    pub fn new<S, V>(game: Game, expected_values: &papaya::HashMap<GameState, V, S>) -> Self
+    where
+        S: BuildHasher,
+        V: Value + AddAssign + Clone,
+    {
+        assert!(!game.ended());
+
+        let combos: Vec<Combo> = Combo::iter()
+            .filter(|&combo| game.combo(combo).is_none())
+            .collect();
+        let mut combo_values = vec![0.0; combos.len() * DICE_COUNT];
+        for (c, &combo) in combos.iter().enumerate() {
+            // upper section points change the bonus tracking, so look up each
+            // possible count separately; other combos only add their points
+            let face = match combo {
+                Combo::Ones => Some(1),
+                Combo::Twos => Some(2),
+                Combo::Threes => Some(3),
+                Combo::Fours => Some(4),
+                Combo::Fives => Some(5),
+                Combo::Sixes => Some(6),
+                _ => None,
+            };
+            let scored = |points: u8| {
+                let mut game = game;
+                game.set_combo_raw(combo, Some(points));
+                expected_score(game, expected_values).to_f64()
+            };
+            let values = &mut combo_values[c * DICE_COUNT..(c + 1) * DICE_COUNT];
+            match face {
+                Some(face) => {
+                    let by_count: Vec<f64> = (0..=5).map(|count| scored(count * face)).collect();
+                    for (value, dice) in values.iter_mut().zip(transitions::all_dice()) {
+                        let count = dice.iter().filter(|&&die| die == face).count();
+                        *value = by_count[count];
+                    }
+                }
+                None => {
+                    let base = scored(0);
+                    for (value, &dice) in values.iter_mut().zip(transitions::all_dice()) {
+                        *value = base + f64::from(combo.points(dice));
+                    }
+                }
+            }
+        }
+
+        let mut values_0 = vec![f64::NEG_INFINITY; DICE_COUNT];
+        for values in combo_values.chunks_exact(DICE_COUNT) {
+            for (best, &value) in values_0.iter_mut().zip(values) {
+                *best = best.max(value);
+            }
+        }
+        let keep_values_0 = keep_values(&values_0);
+        let values_1 = reroll_values(&values_0, &keep_values_0);
+        let keep_values_1 = keep_values(&values_1);
+        let values_2 = reroll_values(&values_0, &keep_values_1);
+
+        Self {
+            combos,
+            combo_values,
+            values: [values_0, values_1, values_2],
+            keep_values: [keep_values_0, keep_values_1],
+        }
+    }
+
+    pub fn value(&self, dice: Dice, rerolls_left: u8) -> f64 {
+        self.values[usize::from(rerolls_left)][dice.index()]
+    }
+
+    pub fn evaluate(&self, dice: Dice, rerolls_left: u8) -> Vec<(Choice, f64)> {
+        let index = dice.index();
+        let mut choices: Vec<(Choice, f64)> = self
+            .combos
+            .iter()
+            .enumerate()
+            .map(|(c, &combo)| {
+                (
+                    Choice::SelectCombo(combo),
+                    self.combo_values[c * DICE_COUNT + index],
+                )
+            })
+            .collect();
+        if rerolls_left > 0 {
+            let tables = &*TABLES;
+            let keep_values = &self.keep_values[usize::from(rerolls_left) - 1];
+            for &keep in &tables.keeps[tables.keep_offsets[index]..tables.keep_offsets[index + 1]] {
+                let keep = usize::from(keep);
+                let choice = Keep::from_dice(transitions::keep_from_index(keep))
+                    .unwrap()
+                    .reroll_choice(dice.as_slice())
+                    .unwrap();
+                choices.push((choice, keep_values[keep]));
+            }
+        }
+        choices
+    }
+
+    pub fn best_choices(&self, dice: Dice, rerolls_left: u8) -> (Vec<Choice>, f64) {
+        let value = self.value(dice, rerolls_left);
+        let choices = self
+            .evaluate(dice, rerolls_left)
+            .into_iter()
+            .filter_map(|(choice, choice_value)| (choice_value == value).then_some(choice))
+            .collect();
+        (choices, value)
+    }
+
+    // probability of ending the turn by scoring each combo with each hand
+    // when always taking the first of the best choices, combos before rerolls
+    pub fn outcomes(&self, dice: Dice, rerolls_left: u8) -> Vec<(Combo, Dice, f64)> {
+        let mut mass = vec![0.0; DICE_COUNT];
+        mass[dice.index()] = 1.0;
+        self.propagate(mass, rerolls_left)
+    }
+
+    // the same from the start of a turn, before the first roll
+    pub fn turn_outcomes(&self) -> Vec<(Combo, Dice, f64)> {
+        let tables = &*TABLES;
+        let keep = transitions::keep_index(&[]).unwrap();
+        let range = tables.transition_offsets[keep]..tables.transition_offsets[keep + 1];
+        let mut mass = vec![0.0; DICE_COUNT];
+        for (&outcome, &probability) in tables.outcomes[range.clone()]
+            .iter()
+            .zip(&tables.probabilities[range])
+        {
+            mass[usize::from(outcome)] += probability;
+        }
+        self.propagate(mass, 2)
+    }
+
+    fn propagate(&self, mut mass: Vec<f64>, rerolls_left: u8) -> Vec<(Combo, Dice, f64)> {
+        let tables = &*TABLES;
+        let mut scored = vec![0.0; self.combos.len() * DICE_COUNT];
+        for rerolls_left in (0..=usize::from(rerolls_left)).rev() {
+            let mut rolled = vec![0.0; DICE_COUNT];
+            for (dice, &probability) in mass.iter().enumerate() {
+                if probability == 0.0 {
+                    continue;
+                }
+                let value = self.values[rerolls_left][dice];
+                let combo = (0..self.combos.len())
+                    .find(|&c| self.combo_values[c * DICE_COUNT + dice] == value);
+                if let Some(c) = combo {
+                    scored[c * DICE_COUNT + dice] += probability;
+                    continue;
+                }
+                let keep_values = &self.keep_values[rerolls_left - 1];
+                let keep = tables.keeps[tables.keep_offsets[dice]..tables.keep_offsets[dice + 1]]
+                    .iter()
+                    .map(|&keep| usize::from(keep))
+                    .find(|&keep| keep_values[keep] == value)
+                    .unwrap();
+                let range = tables.transition_offsets[keep]..tables.transition_offsets[keep + 1];
+                for (&outcome, &outcome_probability) in tables.outcomes[range.clone()]
+                    .iter()
+                    .zip(&tables.probabilities[range])
+                {
+                    rolled[usize::from(outcome)] += probability * outcome_probability;
+                }
+            }
+            mass = rolled;
+        }
+
+        let mut outcomes = Vec::new();
+        for (c, &combo) in self.combos.iter().enumerate() {
+            for (dice, &probability) in scored[c * DICE_COUNT..(c + 1) * DICE_COUNT]
+                .iter()
+                .enumerate()
+            {
+                if probability > 0.0 {
+                    outcomes.push((combo, transitions::dice_from_index(dice), probability));
+                }
+            }
+        }
+        outcomes
+    }
+}
+
+fn keep_values(values: &[f64]) -> Vec<f64> {
+    let tables = &*TABLES;
+    (0..KEEP_COUNT)
+        .map(|keep| {
+            let range = tables.transition_offsets[keep]..tables.transition_offsets[keep + 1];
+            tables.outcomes[range.clone()]
+                .iter()
+                .zip(&tables.probabilities[range])
+                .map(|(&outcome, probability)| probability * values[usize::from(outcome)])
+                .sum()
+        })
+        .collect()
+}
+
+fn reroll_values(values_0: &[f64], keep_values: &[f64]) -> Vec<f64> {
+    let tables = &*TABLES;
+    (0..DICE_COUNT)
+        .map(|dice| {
+            tables.keeps[tables.keep_offsets[dice]..tables.keep_offsets[dice + 1]]
+                .iter()
+                .map(|&keep| keep_values[usize::from(keep)])
+                .fold(values_0[dice], f64::max)
+        })
+        .collect()
+}
+
+pub fn best_choices<S, V>(
+    game: Game,
+    expected_values: &papaya::HashMap<GameState, V, S>,
+) -> (Vec<Choice>, f64)
+where
+    S: BuildHasher,
+    V: Value + AddAssign + Clone,
+{
+    TurnTable::new(game, expected_values).best_choices(game.dice(), game.rerolls_left())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng as _};
+    use rand_chacha::ChaCha8Rng;
+    use rustc_hash::FxBuildHasher;
+    use yatzy_compute_expected_values::state_from_game;
+
+    use super::*;
+    use crate::{best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls, choice_value};
+
+    type ExpectedValues = papaya::HashMap<GameState, f64, FxBuildHasher>;
+
+    // a game with two open combos, so that every state after this turn is a
+    // last-turn state and the table only needs those
+    fn position(rng: &mut ChaCha8Rng, rerolls_left: u8, expected_values: &ExpectedValues) -> Game {
+        let mut game = Game::new_random(rng);
+        let first = rng.random_range(0..15);
+        let second = (first + rng.random_range(1..15)) % 15;
+        let open: Vec<Combo> = Combo::iter()
+            .enumerate()
+            .filter_map(|(i, combo)| (i == first || i == second).then_some(combo))
+            .collect();
+        for combo in Combo::iter().filter(|combo| !open.contains(combo)) {
+            game.set_combo_raw(combo, Some(combo.points(Dice::new_random(rng))));
+        }
+        game.set_rerolls(rerolls_left);
+
+        let expected_values = expected_values.pin();
+        for &combo in &open {
+            for &dice in transitions::all_dice() {
+                let mut next = game;
+                next.set_combo_raw(combo, Some(combo.points(dice)));
+                let state = state_from_game(next);
+                if expected_values.get(&state).is_none() {
+                    expected_values.insert(state, rng.random_range(0.0..50.0));
+                }
+            }
+        }
+        game
+    }
+
+    fn check(rerolls_left: u8) {
+        let mut rng = ChaCha8Rng::seed_from_u64(u64::from(rerolls_left));
+        let expected_values = ExpectedValues::with_hasher(FxBuildHasher);
+        for _ in 0..20 {
+            let game = position(&mut rng, rerolls_left, &expected_values);
+            let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+            let (generic_choices, generic_value) = match rerolls_left {
+                0 => best_choice_0_rerolls::<_, FxBuildHasher, _, f64>(
+                    game,
+                    &expected_values,
+                    &cache,
+                ),
+                1 => {
+                    best_choice_1_reroll::<_, FxBuildHasher, _, f64>(game, &expected_values, &cache)
+                }
+                2 => best_choice_2_rerolls::<_, FxBuildHasher, _, f64>(
+                    game,
+                    &expected_values,
+                    &cache,
+                ),
+                _ => unreachable!(),
+            };
+            let (table_choices, table_value) =
+                TurnTable::new(game, &expected_values).best_choices(game.dice(), rerolls_left);
+
+            assert!(
+                (generic_value - table_value).abs() < 1e-9,
+                "{game:?}: generic value {generic_value}, table value {table_value}"
+            );
+            assert!(!table_choices.is_empty());
+            // rounding may break near ties differently
+            for choice in table_choices {
+                assert!(
+                    generic_choices.contains(&choice)
+                        || generic_value
+                            - choice_value::<_, FxBuildHasher, _, f64>(
+                                game,
+                                choice,
+                                &expected_values,
+                                &cache,
+                            )
+                            < 1e-9,
+                    "{game:?}: table chose {choice:?}, generic chose {generic_choices:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn best_choices_0_rerolls() {
+        check(0);
+    }
+
+    #[test]
+    fn best_choices_1_reroll() {
+        check(1);
+    }
+
+    #[test]
+    fn best_choices_2_rerolls() {
+        check(2);
+    }
+}
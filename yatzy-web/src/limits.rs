@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::{AppendHeaders, IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::{AppState, listener::ClientAddr};
+
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+    burst: f64,
+    per_second: f64,
+    trust_forwarded_for: bool,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32, burst: u32, trust_forwarded_for: bool) -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            burst: f64::from(burst),
+            per_second: f64::from(per_minute) / 60.0,
+            trust_forwarded_for,
+        }
+    }
+
+    fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter mutex poisoned");
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            let full_after = Duration::from_secs_f64(self.burst / self.per_second);
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < full_after);
+        }
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            // clients rotating addresses faster than buckets refill; forget the
+            // least recently seen tenth, which lets them start over with a full burst
+            let mut updated: Vec<Instant> = buckets.values().map(|bucket| bucket.updated).collect();
+            let (_, &mut cutoff, _) = updated.select_nth_unstable(MAX_TRACKED_CLIENTS / 10);
+            buckets.retain(|_, bucket| bucket.updated > cutoff);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        let forwarded = self
+            .trust_forwarded_for
+            .then(|| forwarded_for(request.headers()))
+            .flatten();
+        forwarded.or_else(|| {
+            request
+                .extensions()
+                .get::<ConnectInfo<ClientAddr>>()
+                .and_then(|ConnectInfo(ClientAddr(addr))| addr.map(|addr| addr.ip()))
+        })
+    }
+}
+
+// only the last entry is added by the trusted proxy; anything before it comes
+// from the client
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    let value = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .next_back()?
+        .to_str()
+        .ok()?;
+    value.rsplit(',').next()?.trim().parse().ok()
+}
+
+pub fn retry_after_response(status: StatusCode, retry_after: Duration, error: &str) -> Response {
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    (
+        status,
+        AppendHeaders([(RETRY_AFTER, seconds.max(1).to_string())]),
+        Json(json!({ "errors": [error] })),
+    )
+        .into_response()
+}
+
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(rate_limiter) = &state.rate_limiter else {
+        return next.run(request).await;
+    };
+    // requests over a Unix socket without a forwarded address are not limited
+    let Some(ip) = rate_limiter.client_ip(&request) else {
+        return next.run(request).await;
+    };
+    match rate_limiter.check(ip) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => retry_after_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            retry_after,
+            "too many requests",
+        ),
+    }
+}
+
+pub fn new_rate_limiter(
+    per_minute: Option<u32>,
+    burst: Option<u32>,
+    trust_forwarded_for: bool,
+) -> Option<Arc<RateLimiter>> {
+    let per_minute = per_minute?;
+    Some(Arc::new(RateLimiter::new(
+        per_minute,
+        burst.unwrap_or(per_minute),
+        trust_forwarded_for,
+    )))
+}
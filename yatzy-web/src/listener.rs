@@ -0,0 +1,179 @@
+use std::{
+    fs::Permissions,
+    io,
+    net::SocketAddr,
+    os::unix::fs::{FileTypeExt as _, PermissionsExt as _},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::connect_info::Connected,
+    serve::{IncomingStream, Listener},
+};
+use tokio::{
+    net::{TcpListener, TcpStream, UnixListener},
+    sync::mpsc,
+};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject as _},
+    },
+    server::TlsStream,
+};
+
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug)]
+pub struct ClientAddr(pub Option<SocketAddr>);
+
+impl Connected<IncomingStream<'_, TcpListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        Self(Some(*stream.remote_addr()))
+    }
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        Self(Some(*stream.remote_addr()))
+    }
+}
+
+impl Connected<IncomingStream<'_, UnixListener>> for ClientAddr {
+    fn connect_info(_stream: IncomingStream<'_, UnixListener>) -> Self {
+        Self(None)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error("failed to read certificate chain: {0}")]
+    Certificates(tokio_rustls::rustls::pki_types::pem::Error),
+    #[error("certificate file contains no certificates")]
+    NoCertificates,
+    #[error("failed to read private key: {0}")]
+    PrivateKey(tokio_rustls::rustls::pki_types::pem::Error),
+    #[error("invalid certificate or key: {0}")]
+    Rustls(#[from] tokio_rustls::rustls::Error),
+}
+
+pub fn tls_acceptor(cert_path: &Path, key_path: &Path) -> Result<TlsAcceptor, TlsConfigError> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .map_err(TlsConfigError::Certificates)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(TlsConfigError::Certificates)?;
+    if certs.is_empty() {
+        return Err(TlsConfigError::NoCertificates);
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(TlsConfigError::PrivateKey)?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    receiver: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, acceptor: TlsAcceptor) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel(64);
+
+        // handshakes run in their own tasks so a slow client cannot stall the accept loop
+        tokio::spawn(async move {
+            loop {
+                let (stream, addr) = tokio::select! {
+                    () = sender.closed() => break,
+                    result = listener.accept() => match result {
+                        Ok(accepted) => accepted,
+                        Err(error) => {
+                            tracing::warn!("failed to accept TCP connection: {error}");
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                            continue;
+                        }
+                    },
+                };
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if let Ok(Ok(stream)) =
+                        tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await
+                    {
+                        _ = sender.send((stream, addr)).await;
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            receiver,
+        })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.receiver.recv().await {
+            Some(accepted) => accepted,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnixSocketError {
+    #[error("another server is listening on `{0}`")]
+    InUse(String),
+    #[error("`{0}` exists and is not a socket")]
+    NotASocket(String),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+pub fn bind_unix_socket(
+    path: &Path,
+    mode: Option<u32>,
+    remove_stale: bool,
+) -> Result<UnixListener, UnixSocketError> {
+    if remove_stale {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) => {
+                if !metadata.file_type().is_socket() {
+                    return Err(UnixSocketError::NotASocket(path.display().to_string()));
+                }
+                if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                    return Err(UnixSocketError::InUse(path.display().to_string()));
+                }
+                std::fs::remove_file(path)?;
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error.into());
+            }
+        }
+    }
+
+    let listener = UnixListener::bind(path)?;
+    if let Some(mode) = mode {
+        std::fs::set_permissions(path, Permissions::from_mode(mode))?;
+    }
+    Ok(listener)
+}
@@ -1,34 +1,86 @@
-use std::{net::IpAddr, path::PathBuf};
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    net::IpAddr,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use axum::{
     Json, Router,
-    extract::RawQuery,
-    http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
-    response::{AppendHeaders, IntoResponse},
-    routing::get,
+    body::Body,
+    extract::{DefaultBodyLimit, MatchedPath, RawQuery, Request, State},
+    http::{
+        HeaderMap, HeaderValue, Method, StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+    middleware::{self, Next},
+    response::{AppendHeaders, IntoResponse, Response},
+    routing::{get, post},
 };
 use clap::Parser;
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
 use num_rational::Ratio;
 use pct_str::PctStr;
+use rayon::iter::{IndexedParallelIterator as _, IntoParallelIterator as _, ParallelIterator as _};
 use regex::Regex;
 use rustc_hash::FxBuildHasher;
 use serde::Deserialize;
 use serde_json::{Map, Value, json};
-use tokio::net::{TcpListener, UnixListener};
-use yatzy::{Combo, Game, GameOptions};
+use subtle::ConstantTimeEq as _;
+use tokio::{
+    net::TcpListener,
+    signal::unix::{SignalKind, signal},
+    sync::{Semaphore, mpsc, watch},
+    task::JoinSet,
+};
+use tokio_stream::{StreamExt as _, wrappers::ReceiverStream};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::{DefaultOnFailure, DefaultOnResponse, TraceLayer},
+};
+use tracing::{Level, Span};
+use tracing_subscriber::EnvFilter;
+use yatzy::{Combo, Die, Game, GameOptions, NewGameError};
 use yatzy_solver::{
-    Choice, GameState, best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls,
+    Choice,
+    analysis::{ComboProjection, analyze, project},
+    best_choice_0_rerolls, best_choice_1_reroll, best_choice_2_rerolls, turn,
+};
+
+use crate::{
+    limits::{RateLimiter, retry_after_response},
+    listener::{ClientAddr, TlsListener},
+    logging::{LogFormat, REQUEST_ID_HEADER},
+    metrics::METRICS,
+    noise::{Solver, SolverError, SolverMode, noisy_choice},
+    table::{ExpectedValues, ReloadError},
 };
 
+mod limits;
+mod listener;
+mod logging;
+mod metrics;
+mod noise;
+mod table;
+
 lazy_static! {
     static ref DICE_REGEX: Regex =
         Regex::new(r"^[1-6],[1-6],[1-6],[1-6],[1-6]$").expect("invalid regex");
-    static ref EXPECTED_VALUES: papaya::HashMap<GameState, Ratio<BigUint>, FxBuildHasher> =
-        papaya::HashMap::with_capacity_and_hasher(958_974, FxBuildHasher);
 }
 
+type Cache = papaya::HashMap<
+    (Game, Option<Choice>),
+    (Option<HashSet<Choice, FxBuildHasher>>, Ratio<BigUint>),
+    FxBuildHasher,
+>;
+
 #[derive(Clone, Debug, Parser)]
 #[command(version, about)]
 struct Args {
@@ -39,62 +91,292 @@ struct Args {
 #[derive(Clone, Debug, Deserialize)]
 struct ConfigInput {
     expected_values_path: PathBuf,
+    projections_path: Option<PathBuf>,
     tcp_listen_address: Option<IpAddr>,
     tcp_listen_port: Option<u16>,
     unix_socket_path: Option<PathBuf>,
+    listeners: Option<Vec<ListenerInput>>,
+    allowed_origins: Option<Vec<String>>,
+    admin_token: Option<String>,
+    shutdown_timeout_seconds: Option<u64>,
+    log_format: Option<LogFormat>,
+    log_level: Option<String>,
+    slow_solver_threshold_ms: Option<u64>,
+    exact_solver: Option<bool>,
+    max_batch_size: Option<usize>,
+    compute_timeout_ms: Option<u64>,
+    max_in_flight_jobs: Option<usize>,
+    retry_after_seconds: Option<u64>,
+    rate_limit_per_minute: Option<u32>,
+    rate_limit_burst: Option<u32>,
+    trust_forwarded_for: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ListenerInput {
+    Tcp {
+        address: IpAddr,
+        port: u16,
+        tls_cert_path: Option<PathBuf>,
+        tls_key_path: Option<PathBuf>,
+    },
+    Unix {
+        path: PathBuf,
+        mode: Option<u32>,
+        remove_stale: Option<bool>,
+    },
 }
 
 #[derive(Clone, Debug)]
 enum Socket {
-    Tcp(IpAddr, u16),
-    Unix(PathBuf),
+    Tcp {
+        addr: IpAddr,
+        port: u16,
+        tls: Option<(PathBuf, PathBuf)>,
+    },
+    Unix {
+        path: PathBuf,
+        mode: Option<u32>,
+        remove_stale: bool,
+    },
 }
 
 #[derive(Clone, Debug)]
+enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+#[derive(Debug)]
 struct Config {
     expected_values_path: PathBuf,
-    socket: Socket,
+    projections_path: Option<PathBuf>,
+    sockets: Vec<Socket>,
+    allowed_origins: AllowedOrigins,
+    admin_token: Option<String>,
+    shutdown_timeout: Duration,
+    log_format: LogFormat,
+    log_filter: EnvFilter,
+    slow_solver_threshold: Duration,
+    exact_solver: bool,
+    max_batch_size: usize,
+    compute_timeout: Option<Duration>,
+    max_in_flight_jobs: usize,
+    retry_after: Duration,
+    rate_limit_per_minute: Option<u32>,
+    rate_limit_burst: Option<u32>,
+    trust_forwarded_for: bool,
 }
 
-#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[derive(Clone, Debug)]
+struct AppState {
+    expected_values_path: Arc<PathBuf>,
+    projections_path: Option<Arc<PathBuf>>,
+    admin_token: Option<Arc<str>>,
+    slow_solver_threshold: Duration,
+    exact_solver: bool,
+    max_batch_size: usize,
+    compute_timeout: Option<Duration>,
+    jobs: Arc<Semaphore>,
+    retry_after: Duration,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
 enum ConfigError {
+    #[error("invalid allowed origin `{0}`")]
+    InvalidAllowedOrigin(String),
+    #[error("admin token must not be empty")]
+    EmptyAdminToken,
+    #[error("compute timeout must be at least 1 ms")]
+    InvalidComputeTimeout,
+    #[error("invalid log level `{0}`")]
+    InvalidLogLevel(String),
+    #[error("maximum batch size must be at least 1")]
+    InvalidMaxBatchSize,
+    #[error("maximum number of in-flight jobs must be at least 1")]
+    InvalidMaxInFlightJobs,
+    #[error("rate limit burst must be at least 1")]
+    InvalidRateLimitBurst,
+    #[error("rate limit must be at least 1 request per minute")]
+    InvalidRateLimitPerMinute,
+    #[error("invalid Unix socket mode {0:o}")]
+    InvalidUnixSocketMode(u32),
+    #[error("`listeners` cannot be combined with `tcp_listen_*` or `unix_socket_path`")]
+    ListenersAndLegacyListenerMutuallyExclusive,
     #[error("missing TCP listener address")]
     MissingTcpAddress,
     #[error("missing TCP listener port")]
     MissingTcpPort,
+    #[error("configure both a TLS certificate and a TLS key, or neither")]
+    MissingTlsCertOrKey,
     #[error("configure a TCP listener or a Unix socket listener")]
     NoListener,
     #[error("configure either a TCP listener or a Unix socket listener, not both")]
     TcpAndUnixListenersMutuallyExclusive,
 }
 
+impl TryFrom<ListenerInput> for Socket {
+    type Error = ConfigError;
+
+    fn try_from(value: ListenerInput) -> Result<Self, Self::Error> {
+        match value {
+            ListenerInput::Tcp {
+                address,
+                port,
+                tls_cert_path,
+                tls_key_path,
+            } => {
+                let tls = match (tls_cert_path, tls_key_path) {
+                    (None, None) => None,
+                    (Some(cert_path), Some(key_path)) => Some((cert_path, key_path)),
+                    (Some(_), None) | (None, Some(_)) => {
+                        return Err(ConfigError::MissingTlsCertOrKey);
+                    }
+                };
+                Ok(Self::Tcp {
+                    addr: address,
+                    port,
+                    tls,
+                })
+            }
+            ListenerInput::Unix {
+                path,
+                mode,
+                remove_stale,
+            } => {
+                if let Some(mode) = mode.filter(|&mode| mode > 0o777) {
+                    return Err(ConfigError::InvalidUnixSocketMode(mode));
+                }
+                Ok(Self::Unix {
+                    path,
+                    mode,
+                    remove_stale: remove_stale.unwrap_or(false),
+                })
+            }
+        }
+    }
+}
+
 impl TryFrom<ConfigInput> for Config {
     type Error = ConfigError;
 
     fn try_from(value: ConfigInput) -> Result<Self, Self::Error> {
-        let socket = match (
+        let legacy_socket = match (
             value.unix_socket_path,
             value.tcp_listen_address,
             value.tcp_listen_port,
         ) {
-            (None, None, None) => {
-                return Err(ConfigError::NoListener);
-            }
+            (None, None, None) => None,
             (None, None, Some(_)) => {
                 return Err(ConfigError::MissingTcpAddress);
             }
             (None, Some(_), None) => {
                 return Err(ConfigError::MissingTcpPort);
             }
-            (None, Some(addr), Some(port)) => Socket::Tcp(addr, port),
-            (Some(path), None, None) => Socket::Unix(path),
+            (None, Some(addr), Some(port)) => Some(Socket::Tcp {
+                addr,
+                port,
+                tls: None,
+            }),
+            (Some(path), None, None) => Some(Socket::Unix {
+                path,
+                mode: None,
+                remove_stale: false,
+            }),
             (Some(_), None, Some(_)) | (Some(_), Some(_), None) | (Some(_), Some(_), Some(_)) => {
                 return Err(ConfigError::TcpAndUnixListenersMutuallyExclusive);
             }
         };
+        let sockets = match (legacy_socket, value.listeners) {
+            (None, None) => {
+                return Err(ConfigError::NoListener);
+            }
+            (Some(socket), None) => vec![socket],
+            (None, Some(listeners)) => {
+                if listeners.is_empty() {
+                    return Err(ConfigError::NoListener);
+                }
+                listeners
+                    .into_iter()
+                    .map(Socket::try_from)
+                    .collect::<Result<_, _>>()?
+            }
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::ListenersAndLegacyListenerMutuallyExclusive);
+            }
+        };
+        let allowed_origins = match value.allowed_origins {
+            None => AllowedOrigins::Any,
+            Some(origins) if origins.iter().any(|origin| origin == "*") => AllowedOrigins::Any,
+            Some(origins) => AllowedOrigins::List(
+                origins
+                    .into_iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(&origin)
+                            .map_err(|_| ConfigError::InvalidAllowedOrigin(origin))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        if value.admin_token.as_deref() == Some("") {
+            return Err(ConfigError::EmptyAdminToken);
+        }
+        let log_level = value.log_level.unwrap_or_else(|| String::from("info"));
+        let log_filter = match EnvFilter::try_new(&log_level) {
+            Ok(log_filter) => log_filter,
+            Err(_) => {
+                return Err(ConfigError::InvalidLogLevel(log_level));
+            }
+        };
+        let max_batch_size = match value.max_batch_size {
+            None => 1000,
+            Some(0) => {
+                return Err(ConfigError::InvalidMaxBatchSize);
+            }
+            Some(max_batch_size) => max_batch_size,
+        };
+        let compute_timeout = match value.compute_timeout_ms {
+            None => None,
+            Some(0) => {
+                return Err(ConfigError::InvalidComputeTimeout);
+            }
+            Some(ms) => Some(Duration::from_millis(ms)),
+        };
+        let max_in_flight_jobs = match value.max_in_flight_jobs {
+            None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            Some(0) => {
+                return Err(ConfigError::InvalidMaxInFlightJobs);
+            }
+            Some(max_in_flight_jobs) => max_in_flight_jobs,
+        };
+        if value.rate_limit_per_minute == Some(0) {
+            return Err(ConfigError::InvalidRateLimitPerMinute);
+        }
+        if value.rate_limit_burst == Some(0) {
+            return Err(ConfigError::InvalidRateLimitBurst);
+        }
         Ok(Self {
             expected_values_path: value.expected_values_path,
-            socket,
+            projections_path: value.projections_path,
+            sockets,
+            allowed_origins,
+            admin_token: value.admin_token,
+            shutdown_timeout: Duration::from_secs(value.shutdown_timeout_seconds.unwrap_or(30)),
+            log_format: value.log_format.unwrap_or_default(),
+            log_filter,
+            slow_solver_threshold: Duration::from_millis(
+                value.slow_solver_threshold_ms.unwrap_or(1000),
+            ),
+            exact_solver: value.exact_solver.unwrap_or(true),
+            max_batch_size,
+            compute_timeout,
+            max_in_flight_jobs,
+            retry_after: Duration::from_secs(value.retry_after_seconds.unwrap_or(1)),
+            rate_limit_per_minute: value.rate_limit_per_minute,
+            rate_limit_burst: value.rate_limit_burst,
+            trust_forwarded_for: value.trust_forwarded_for.unwrap_or(false),
         })
     }
 }
@@ -125,68 +407,326 @@ async fn main() {
         }
     };
 
-    let expected_values: std::collections::HashMap<GameState, Ratio<BigUint>> =
-        match std::fs::read(&config.expected_values_path) {
-            Ok(bytes) => match postcard::from_bytes(&bytes) {
-                Ok(map) => map,
-                Err(error) => {
-                    eprintln!(
-                        "failed to parse `{}`: {}",
-                        config.expected_values_path.display(),
-                        error
-                    );
-                    std::process::exit(3);
-                }
-            },
-            Err(error) => {
-                eprintln!(
-                    "failed to read `{}`: {}",
-                    config.expected_values_path.display(),
-                    error
-                );
-                std::process::exit(3);
-            }
-        };
+    logging::init(config.log_format, config.log_filter);
 
-    let expected_values_static = EXPECTED_VALUES.pin();
-    for (state, value) in expected_values {
-        expected_values_static.insert(state, value);
+    let expected_values_path = config.expected_values_path.clone();
+    let projections_path = config.projections_path.clone();
+    // taken before the SIGHUP handler exists, so that an early reload is refused
+    let guard = table::lock_loading().expect("the table is not loaded yet");
+    tokio::task::spawn_blocking(move || {
+        if let Err(error) = table::load(&expected_values_path, projections_path.as_deref(), guard) {
+            tracing::error!("{error}");
+            std::process::exit(3);
+        }
+    });
+    tokio::spawn(reload_on_sighup(
+        config.expected_values_path.clone(),
+        config.projections_path.clone(),
+    ));
+
+    let state = AppState {
+        expected_values_path: Arc::new(config.expected_values_path),
+        projections_path: config.projections_path.map(Arc::new),
+        admin_token: config.admin_token.map(Arc::from),
+        slow_solver_threshold: config.slow_solver_threshold,
+        exact_solver: config.exact_solver,
+        max_batch_size: config.max_batch_size,
+        compute_timeout: config.compute_timeout,
+        jobs: Arc::new(Semaphore::new(config.max_in_flight_jobs)),
+        retry_after: config.retry_after,
+        rate_limiter: limits::new_rate_limiter(
+            config.rate_limit_per_minute,
+            config.rate_limit_burst,
+            config.trust_forwarded_for,
+        ),
+    };
+    let cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([CONTENT_TYPE])
+        .allow_origin(match config.allowed_origins {
+            AllowedOrigins::Any => AllowOrigin::any(),
+            AllowedOrigins::List(origins) => AllowOrigin::list(origins),
+        });
+    let advice = Router::new()
+        .route("/", get(index))
+        .route(
+            "/api/v1/advice/batch",
+            post(advice_batch).layer(DefaultBodyLimit::max(
+                config.max_batch_size.saturating_mul(1024),
+            )),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            limits::rate_limit,
+        ))
+        .layer(cors);
+    let mut app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics));
+    if state.admin_token.is_some() {
+        app = app.route("/admin/reload", post(admin_reload));
     }
+    let app = app
+        .merge(advice)
+        .layer(middleware::from_fn(track_requests))
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(logging::request_span)
+                .on_response(DefaultOnResponse::new().level(Level::INFO))
+                .on_failure(DefaultOnFailure::new().level(Level::ERROR)),
+        )
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+        .with_state(state);
 
-    let app = Router::new().route("/", get(index));
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    let mut servers = JoinSet::new();
+    let mut unix_socket_paths = Vec::new();
 
-    match config.socket {
-        Socket::Tcp(addr, port) => {
-            let listener = match TcpListener::bind((addr, port)).await {
-                Ok(listener) => listener,
-                Err(error) => {
-                    eprintln!("failed to bind TCP listener: {error}");
-                    std::process::exit(4);
-                }
-            };
-            match axum::serve(listener, app).await {
-                Ok(()) => {}
-                Err(error) => {
-                    eprintln!("server failed: {error}");
-                }
+    for socket in config.sockets {
+        let app = app.clone();
+        let shutdown = wait_for_shutdown(shutdown_receiver.clone());
+        match socket {
+            Socket::Tcp {
+                addr,
+                port,
+                tls: None,
+            } => {
+                let listener = match TcpListener::bind((addr, port)).await {
+                    Ok(listener) => listener,
+                    Err(error) => {
+                        tracing::error!(%addr, port, "failed to bind TCP listener: {error}");
+                        std::process::exit(4);
+                    }
+                };
+                tracing::info!(%addr, port, "listening");
+                servers.spawn(async move {
+                    axum::serve(
+                        listener,
+                        app.into_make_service_with_connect_info::<ClientAddr>(),
+                    )
+                    .with_graceful_shutdown(shutdown)
+                    .await
+                });
+            }
+            Socket::Tcp {
+                addr,
+                port,
+                tls: Some((cert_path, key_path)),
+            } => {
+                let acceptor = match listener::tls_acceptor(&cert_path, &key_path) {
+                    Ok(acceptor) => acceptor,
+                    Err(error) => {
+                        tracing::error!("failed to configure TLS: {error}");
+                        std::process::exit(4);
+                    }
+                };
+                let listener = match TcpListener::bind((addr, port)).await {
+                    Ok(listener) => listener,
+                    Err(error) => {
+                        tracing::error!(%addr, port, "failed to bind TCP listener: {error}");
+                        std::process::exit(4);
+                    }
+                };
+                let listener = match TlsListener::new(listener, acceptor) {
+                    Ok(listener) => listener,
+                    Err(error) => {
+                        tracing::error!("failed to create TLS listener: {error}");
+                        std::process::exit(4);
+                    }
+                };
+                tracing::info!(%addr, port, "listening with TLS");
+                servers.spawn(async move {
+                    axum::serve(
+                        listener,
+                        app.into_make_service_with_connect_info::<ClientAddr>(),
+                    )
+                    .with_graceful_shutdown(shutdown)
+                    .await
+                });
+            }
+            Socket::Unix {
+                path,
+                mode,
+                remove_stale,
+            } => {
+                let listener = match listener::bind_unix_socket(&path, mode, remove_stale) {
+                    Ok(listener) => listener,
+                    Err(error) => {
+                        tracing::error!(path = %path.display(), "failed to create Unix socket: {error}");
+                        std::process::exit(4);
+                    }
+                };
+                tracing::info!(path = %path.display(), "listening on Unix socket");
+                unix_socket_paths.push(path);
+                servers.spawn(async move {
+                    axum::serve(
+                        listener,
+                        app.into_make_service_with_connect_info::<ClientAddr>(),
+                    )
+                    .with_graceful_shutdown(shutdown)
+                    .await
+                });
             }
         }
-        Socket::Unix(path) => {
-            let listener = match UnixListener::bind(path) {
-                Ok(listener) => listener,
-                Err(error) => {
-                    eprintln!("failed to create Unix socket: {error}");
-                    std::process::exit(4);
-                }
-            };
-            match axum::serve(listener, app).await {
-                Ok(()) => {}
-                Err(error) => {
-                    eprintln!("server failed: {error}");
-                }
+    }
+
+    tokio::select! {
+        () = shutdown_signal() => {
+            tracing::info!("shutting down, waiting for in-flight requests");
+        }
+        Some(result) = servers.join_next() => {
+            if let Ok(Err(error)) = result {
+                tracing::error!("server failed: {error}");
             }
         }
     }
+    shutdown_sender.send_replace(true);
+
+    let drain = async { while servers.join_next().await.is_some() {} };
+    if tokio::time::timeout(config.shutdown_timeout, drain)
+        .await
+        .is_err()
+    {
+        tracing::warn!("timed out waiting for in-flight requests");
+    }
+
+    for path in unix_socket_paths {
+        if let Err(error) = std::fs::remove_file(&path) {
+            tracing::warn!(path = %path.display(), "failed to remove Unix socket: {error}");
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(error) => {
+            tracing::error!("failed to install SIGTERM handler: {error}");
+            std::process::exit(4);
+        }
+    };
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
+
+async fn wait_for_shutdown(mut receiver: watch::Receiver<bool>) {
+    _ = receiver.wait_for(|&shutdown| shutdown).await;
+}
+
+async fn reload_on_sighup(path: PathBuf, projections_path: Option<PathBuf>) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(error) => {
+            tracing::error!("failed to install SIGHUP handler: {error}");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        tracing::info!("received SIGHUP, reloading expected values");
+        let path = path.clone();
+        let projections_path = projections_path.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(ReloadError::InProgress) = table::reload(&path, projections_path.as_deref())
+            {
+                tracing::warn!("ignoring SIGHUP: {}", ReloadError::InProgress);
+            }
+        });
+    }
+}
+
+async fn admin_reload(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let authorized = match (token, state.admin_token.as_deref()) {
+        (Some(token), Some(admin_token)) => token.as_bytes().ct_eq(admin_token.as_bytes()).into(),
+        _ => false,
+    };
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "errors": ["invalid admin token"] })),
+        )
+            .into_response();
+    }
+
+    let path = state.expected_values_path.clone();
+    let projections_path = state.projections_path.clone();
+    match tokio::task::spawn_blocking(move || {
+        table::reload(&path, projections_path.as_deref().map(PathBuf::as_path))
+    })
+    .await
+    {
+        Ok(Ok(states)) => (Json(json!({ "states": states })),).into_response(),
+        Ok(Err(ReloadError::InProgress)) => (
+            StatusCode::CONFLICT,
+            Json(json!({ "errors": [ReloadError::InProgress.to_string()] })),
+        )
+            .into_response(),
+        Ok(Err(ReloadError::Load(error))) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": [error.to_string()] })),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "errors": ["reload task panicked"] })),
+        )
+            .into_response(),
+    }
+}
+
+async fn track_requests(request: Request, next: Next) -> Response {
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(path) => String::from(path.as_str()),
+        None => String::from("unmatched"),
+    };
+    let response = next.run(request).await;
+    METRICS.observe_request(&path, response.status().as_u16());
+    response
+}
+
+async fn healthz() -> impl IntoResponse {
+    "ok\n"
+}
+
+async fn readyz() -> impl IntoResponse {
+    if METRICS.is_ready() {
+        (StatusCode::OK, "ready\n")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not ready\n")
+    }
+}
+
+async fn metrics() -> impl IntoResponse {
+    (
+        AppendHeaders([(CONTENT_TYPE, "text/plain; version=0.0.4")]),
+        METRICS.render(),
+    )
+}
+
+fn not_ready_response() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "errors": ["expected values are still loading"] })),
+    )
+        .into_response()
+}
+
+const ANALYSIS_UNAVAILABLE: &str = "analysis is not available without projections";
+
+// analyses come from projections computed offline, never on demand
+fn analysis_unavailable_response() -> Response {
+    (
+        StatusCode::NOT_IMPLEMENTED,
+        Json(json!({ "errors": [ANALYSIS_UNAVAILABLE] })),
+    )
+        .into_response()
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, thiserror::Error)]
@@ -201,9 +741,23 @@ enum ParseIndexQueryStringError {
     MissingValue(String),
     #[error("unknown parameter `{0}`")]
     UnknownParameter(String),
+    #[error("parameter `{0}` is not used by the selected mode")]
+    UnusedParameter(String),
 }
 
-fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStringError>> {
+impl From<SolverError> for ParseIndexQueryStringError {
+    fn from(error: SolverError) -> Self {
+        match error {
+            SolverError::MissingValue(key) => Self::MissingValue(String::from(key)),
+            SolverError::InvalidValue(key) => Self::InvalidValue(String::from(key)),
+            SolverError::UnusedParameter(key) => Self::UnusedParameter(String::from(key)),
+        }
+    }
+}
+
+fn parse_index_query_string(
+    query: &str,
+) -> Result<(Game, Solver, bool, bool), Vec<ParseIndexQueryStringError>> {
     let query = match PctStr::new(query) {
         Ok(query) => query.decode(),
         Err(_) => {
@@ -228,6 +782,12 @@ fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStri
         "full_house",
         "chance",
         "yatzy",
+        "mode",
+        "temperature",
+        "max_loss",
+        "seed",
+        "analysis",
+        "projection",
     ];
 
     let mut dice = None;
@@ -247,6 +807,12 @@ fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStri
     let mut full_house = None;
     let mut chance = None;
     let mut yatzy = None;
+    let mut mode = None;
+    let mut temperature = None;
+    let mut max_loss = None;
+    let mut seed = None;
+    let mut analysis = None;
+    let mut projection = None;
 
     let mut errors = Vec::new();
 
@@ -724,6 +1290,116 @@ fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStri
                     }
                 };
             }
+            "mode" => {
+                if mode.is_some() {
+                    errors.push(ParseIndexQueryStringError::DuplicateParameter(
+                        String::from(key),
+                    ));
+                    continue;
+                }
+                mode = match value {
+                    "" => {
+                        errors.push(ParseIndexQueryStringError::MissingValue(String::from(key)));
+                        continue;
+                    }
+                    "optimal" => Some(Ok(SolverMode::Optimal)),
+                    "softmax" => Some(Ok(SolverMode::Softmax)),
+                    "bounded_loss" => Some(Ok(SolverMode::BoundedLoss)),
+                    _ => {
+                        errors.push(ParseIndexQueryStringError::InvalidValue(String::from(key)));
+                        Some(Err(()))
+                    }
+                };
+            }
+            "temperature" | "max_loss" => {
+                let parameter = if key == "temperature" {
+                    &mut temperature
+                } else {
+                    &mut max_loss
+                };
+                if parameter.is_some() {
+                    errors.push(ParseIndexQueryStringError::DuplicateParameter(
+                        String::from(key),
+                    ));
+                    continue;
+                }
+                *parameter = match value {
+                    "" => {
+                        errors.push(ParseIndexQueryStringError::MissingValue(String::from(key)));
+                        continue;
+                    }
+                    _ => match value.parse::<f64>() {
+                        Ok(value) => Some(Ok(value)),
+                        Err(_) => {
+                            errors
+                                .push(ParseIndexQueryStringError::InvalidValue(String::from(key)));
+                            Some(Err(()))
+                        }
+                    },
+                };
+            }
+            "seed" => {
+                if seed.is_some() {
+                    errors.push(ParseIndexQueryStringError::DuplicateParameter(
+                        String::from(key),
+                    ));
+                    continue;
+                }
+                seed = match value {
+                    "" => {
+                        errors.push(ParseIndexQueryStringError::MissingValue(String::from(key)));
+                        continue;
+                    }
+                    _ => match value.parse::<u64>() {
+                        Ok(value) => Some(Ok(value)),
+                        Err(_) => {
+                            errors
+                                .push(ParseIndexQueryStringError::InvalidValue(String::from(key)));
+                            Some(Err(()))
+                        }
+                    },
+                };
+            }
+            "analysis" => {
+                if analysis.is_some() {
+                    errors.push(ParseIndexQueryStringError::DuplicateParameter(
+                        String::from(key),
+                    ));
+                    continue;
+                }
+                analysis = match value {
+                    "" => {
+                        errors.push(ParseIndexQueryStringError::MissingValue(String::from(key)));
+                        continue;
+                    }
+                    "true" => Some(Ok(true)),
+                    "false" => Some(Ok(false)),
+                    _ => {
+                        errors.push(ParseIndexQueryStringError::InvalidValue(String::from(key)));
+                        Some(Err(()))
+                    }
+                };
+            }
+            "projection" => {
+                if projection.is_some() {
+                    errors.push(ParseIndexQueryStringError::DuplicateParameter(
+                        String::from(key),
+                    ));
+                    continue;
+                }
+                projection = match value {
+                    "" => {
+                        errors.push(ParseIndexQueryStringError::MissingValue(String::from(key)));
+                        continue;
+                    }
+                    "true" => Some(Ok(true)),
+                    "false" => Some(Ok(false)),
+                    _ => {
+                        errors.push(ParseIndexQueryStringError::InvalidValue(String::from(key)));
+                        Some(Err(()))
+                    }
+                };
+            }
             key => {
                 errors.push(ParseIndexQueryStringError::UnknownParameter(String::from(
                     key,
@@ -732,6 +1408,23 @@ fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStri
         }
     }
 
+    let mut solver = None;
+    if let (Ok(mode), Ok(temperature), Ok(max_loss), Ok(seed)) = (
+        mode.unwrap_or(Ok(SolverMode::Optimal)),
+        temperature.transpose(),
+        max_loss.transpose(),
+        seed.transpose(),
+    ) {
+        match Solver::new(mode, temperature, max_loss, seed) {
+            Ok(value) => {
+                solver = Some(value);
+            }
+            Err(error) => {
+                errors.push(error.into());
+            }
+        }
+    }
+
     if dice.is_none() {
         errors.push(ParseIndexQueryStringError::MissingValue(String::from(
             "dice",
@@ -880,17 +1573,34 @@ fn parse_index_query_string(query: &str) -> Result<Game, Vec<ParseIndexQueryStri
             .expect("invalid combo `yatzy`"),
     })
     .expect("invalid game");
-    Ok(game)
+    let analysis = match analysis {
+        Some(analysis) => analysis.expect("invalid `analysis`"),
+        None => false,
+    };
+    let projection = match projection {
+        Some(projection) => projection.expect("invalid `projection`"),
+        None => false,
+    };
+    Ok((
+        game,
+        solver.expect("invalid solver parameters"),
+        analysis,
+        projection,
+    ))
 }
 
-async fn index(RawQuery(query): RawQuery) -> impl IntoResponse {
+async fn index(State(state): State<AppState>, RawQuery(query): RawQuery) -> Response {
+    let Some((expected_values, projections)) = table::current() else {
+        return not_ready_response();
+    };
     let query = match query {
         Some(query) => query,
         None => String::new(),
     };
-    let game = match parse_index_query_string(&query) {
-        Ok(game) => game,
+    let (game, solver, analysis, projection) = match parse_index_query_string(&query) {
+        Ok(parsed) => parsed,
         Err(errors) => {
+            METRICS.observe_validation_failure();
             let mut rv = Map::new();
             rv.insert(
                 String::from("errors"),
@@ -901,90 +1611,417 @@ async fn index(RawQuery(query): RawQuery) -> impl IntoResponse {
                         .collect(),
                 ),
             );
-            return (
-                AppendHeaders([(ACCESS_CONTROL_ALLOW_ORIGIN, "*")]),
-                Json(rv.into()),
-            );
+            return (Json(Value::from(rv)),).into_response();
         }
     };
     if game.ended() {
-        return (
-            AppendHeaders([(ACCESS_CONTROL_ALLOW_ORIGIN, "*")]),
-            Json(json!({ "errors": ["game has ended"] })),
+        METRICS.observe_validation_failure();
+        return (Json(json!({ "errors": ["game has ended"] })),).into_response();
+    }
+    if (analysis || projection) && projections.is_none() {
+        return analysis_unavailable_response();
+    }
+
+    let Ok(permit) = state.jobs.clone().try_acquire_owned() else {
+        return retry_after_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            state.retry_after,
+            "server is busy",
+        );
+    };
+    // the permit is held until the computation finishes, even if the request times out
+    let span = Span::current();
+    let job = tokio::task::spawn_blocking(move || {
+        let _guard = span.enter();
+        let cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        let choices = advise(
+            game,
+            solver,
+            &expected_values,
+            &cache,
+            state.exact_solver,
+            state.slow_solver_threshold,
         );
+        let analysis = projections
+            .as_ref()
+            .filter(|_| analysis)
+            .map(|projections| analyze(game, &expected_values, projections));
+        let projection = projections
+            .as_ref()
+            .filter(|_| projection)
+            .map(|projections| project(game, &expected_values, projections));
+        drop(permit);
+        (choices, analysis, projection)
+    });
+    let (choices, analysis, projection) = match state.compute_timeout {
+        Some(compute_timeout) => match tokio::time::timeout(compute_timeout, job).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(?game, "computation timed out");
+                return retry_after_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    state.retry_after,
+                    "computation timed out",
+                );
+            }
+        },
+        None => job.await,
+    }
+    .expect("solver task panicked");
+    let choices_json = choices_to_json(game, choices);
+
+    if analysis.is_none() && projection.is_none() {
+        return (Json(json!(choices_json)),).into_response();
+    }
+    let mut rv = Map::new();
+    rv.insert(String::from("choices"), json!(choices_json));
+    if let Some(analysis) = analysis {
+        rv.insert(String::from("analysis"), json!(analysis));
+    }
+    if let Some(projection) = projection {
+        rv.insert(String::from("projection"), projection_to_json(projection));
+    }
+    (Json(Value::from(rv)),).into_response()
+}
+
+fn advise(
+    game: Game,
+    solver: Solver,
+    expected_values: &ExpectedValues,
+    cache: &Cache,
+    exact: bool,
+    slow_threshold: Duration,
+) -> Vec<Choice> {
+    let _span = tracing::info_span!(
+        "solver",
+        dice = ?game.dice(),
+        rerolls_left = game.rerolls_left(),
+        noise = ?solver.noise,
+        exact,
+    )
+    .entered();
+    let start = Instant::now();
+    let choices = match solver.noise {
+        Some(noise) => vec![noisy_choice(
+            game,
+            noise,
+            solver.seed,
+            exact,
+            expected_values,
+            cache,
+        )],
+        None => {
+            if exact {
+                best_choices(game, expected_values, cache)
+                    .into_iter()
+                    .collect()
+            } else {
+                turn::best_choices(game, expected_values).0
+            }
+        }
+    };
+    let elapsed = start.elapsed();
+    METRICS.observe_solver(game.rerolls_left(), elapsed);
+    if elapsed >= slow_threshold {
+        tracing::warn!(?game, elapsed_ms = elapsed.as_millis(), "slow solver call");
+    } else {
+        tracing::debug!(elapsed_ms = elapsed.as_millis(), "computed best choices");
     }
+    choices
+}
+
+fn best_choices(
+    game: Game,
+    expected_values: &ExpectedValues,
+    cache: &Cache,
+) -> HashSet<Choice, FxBuildHasher> {
+    if let Some((Some(choices), _)) = cache.pin().get(&(game, None)) {
+        METRICS.observe_cache_lookup(true);
+        return choices.clone();
+    }
+    METRICS.observe_cache_lookup(false);
 
-    let cache = papaya::HashMap::with_hasher(FxBuildHasher);
-    let (choices, _) = match game.rerolls_left() {
+    let (choices, value) = match game.rerolls_left() {
         0 => best_choice_0_rerolls::<_, FxBuildHasher, _, Ratio<BigUint>>(
             game,
-            &EXPECTED_VALUES,
-            &cache,
+            expected_values,
+            cache,
         ),
         1 => best_choice_1_reroll::<_, FxBuildHasher, _, Ratio<BigUint>>(
             game,
-            &EXPECTED_VALUES,
-            &cache,
+            expected_values,
+            cache,
         ),
         2 => best_choice_2_rerolls::<_, FxBuildHasher, _, Ratio<BigUint>>(
             game,
-            &EXPECTED_VALUES,
-            &cache,
+            expected_values,
+            cache,
         ),
         _ => unreachable!(),
     };
+    // best_choice_2_rerolls does not store its own result
+    cache
+        .pin()
+        .insert((game, None), (Some(choices.clone()), value));
+    choices
+}
+
+fn combo_key(combo: Combo) -> &'static str {
+    match combo {
+        Combo::Ones => "ones",
+        Combo::Twos => "twos",
+        Combo::Threes => "threes",
+        Combo::Fours => "fours",
+        Combo::Fives => "fives",
+        Combo::Sixes => "sixes",
+        Combo::OnePair => "one_pair",
+        Combo::TwoPairs => "two_pairs",
+        Combo::ThreeOfAKind => "three_of_a_kind",
+        Combo::FourOfAKind => "four_of_a_kind",
+        Combo::SmallStraight => "small_straight",
+        Combo::LargeStraight => "large_straight",
+        Combo::FullHouse => "full_house",
+        Combo::Chance => "chance",
+        Combo::Yatzy => "yatzy",
+    }
+}
 
-    let choices_json = choices
+fn choices_to_json(game: Game, choices: Vec<Choice>) -> Vec<Value> {
+    let hand = game.dice();
+    choices
         .into_iter()
         .map(|choice| match choice {
-            Choice::SelectCombo(combo) => {
-                let combo_str = match combo {
-                    Combo::Ones => "ones",
-                    Combo::Twos => "twos",
-                    Combo::Threes => "threes",
-                    Combo::Fours => "fours",
-                    Combo::Fives => "fives",
-                    Combo::Sixes => "sixes",
-                    Combo::OnePair => "one_pair",
-                    Combo::TwoPairs => "two_pairs",
-                    Combo::ThreeOfAKind => "three_of_a_kind",
-                    Combo::FourOfAKind => "four_of_a_kind",
-                    Combo::SmallStraight => "small_straight",
-                    Combo::LargeStraight => "large_straight",
-                    Combo::FullHouse => "full_house",
-                    Combo::Chance => "chance",
-                    Combo::Yatzy => "yatzy",
-                };
-                json!({
-                    "choice": "select_combo",
-                    "combo": combo_str,
-                })
-            }
-            Choice::Reroll1(dice) => json!({
-                "choice": "reroll",
-                "dice": dice,
-            }),
-            Choice::Reroll2(dice) => json!({
-                "choice": "reroll",
-                "dice": dice,
-            }),
-            Choice::Reroll3(dice) => json!({
-                "choice": "reroll",
-                "dice": dice,
+            Choice::SelectCombo(combo) => json!({
+                "choice": "select_combo",
+                "combo": combo_key(combo),
             }),
-            Choice::Reroll4(dice) => json!({
+            Choice::Reroll1(_)
+            | Choice::Reroll2(_)
+            | Choice::Reroll3(_)
+            | Choice::Reroll4(_)
+            | Choice::Reroll5(_) => json!({
                 "choice": "reroll",
-                "dice": dice,
-            }),
-            Choice::Reroll5(dice) => json!({
-                "choice": "reroll",
-                "dice": dice,
+                "dice": choice.rerolled_dice().unwrap(),
+                "keep": choice.keep(hand.as_slice()).unwrap().dice(),
             }),
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
+
+fn projection_to_json(projection: Vec<(Combo, ComboProjection)>) -> Value {
+    projection
+        .into_iter()
+        .map(|(combo, combo_projection)| (String::from(combo_key(combo)), json!(combo_projection)))
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameInput {
+    dice: [Die; 5],
+    rerolls_left: u8,
+    ones: Option<u8>,
+    twos: Option<u8>,
+    threes: Option<u8>,
+    fours: Option<u8>,
+    fives: Option<u8>,
+    sixes: Option<u8>,
+    one_pair: Option<u8>,
+    two_pairs: Option<u8>,
+    three_of_a_kind: Option<u8>,
+    four_of_a_kind: Option<u8>,
+    small_straight: Option<u8>,
+    large_straight: Option<u8>,
+    full_house: Option<u8>,
+    chance: Option<u8>,
+    yatzy: Option<u8>,
+    #[serde(default)]
+    mode: SolverMode,
+    temperature: Option<f64>,
+    max_loss: Option<f64>,
+    seed: Option<u64>,
+    #[serde(default)]
+    analysis: bool,
+    #[serde(default)]
+    projection: bool,
+}
+
+fn parse_batch_game(value: Value) -> Result<(Game, Solver, bool, bool), String> {
+    let input: GameInput = match serde_json::from_value(value) {
+        Ok(input) => input,
+        Err(error) => {
+            return Err(error.to_string());
+        }
+    };
+    let game = match Game::new(GameOptions {
+        dice: input.dice,
+        rerolls_left: input.rerolls_left,
+        ones: input.ones,
+        twos: input.twos,
+        threes: input.threes,
+        fours: input.fours,
+        fives: input.fives,
+        sixes: input.sixes,
+        one_pair: input.one_pair,
+        two_pairs: input.two_pairs,
+        three_of_a_kind: input.three_of_a_kind,
+        four_of_a_kind: input.four_of_a_kind,
+        small_straight: input.small_straight,
+        large_straight: input.large_straight,
+        full_house: input.full_house,
+        chance: input.chance,
+        yatzy: input.yatzy,
+    }) {
+        Ok(game) => game,
+        Err(NewGameError::InvalidCombo(combo)) => {
+            return Err(format!(
+                "invalid value for parameter `{}`",
+                combo_key(combo)
+            ));
+        }
+        Err(NewGameError::InvalidDice(_)) => {
+            return Err(String::from("invalid value for parameter `dice`"));
+        }
+        Err(NewGameError::InvalidRerollsLeft) => {
+            return Err(String::from("invalid value for parameter `rerolls_left`"));
+        }
+    };
+    if game.ended() {
+        return Err(String::from("game has ended"));
+    }
+    let solver = match Solver::new(input.mode, input.temperature, input.max_loss, input.seed) {
+        Ok(solver) => solver,
+        Err(error) => {
+            return Err(error.to_string());
+        }
+    };
+    Ok((game, solver, input.analysis, input.projection))
+}
+
+async fn advice_batch(State(state): State<AppState>, Json(games): Json<Vec<Value>>) -> Response {
+    let Some((expected_values, projections)) = table::current() else {
+        return not_ready_response();
+    };
+    if games.len() > state.max_batch_size {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({
+                "errors": [format!("batch size exceeds the maximum of {}", state.max_batch_size)],
+            })),
+        )
+            .into_response();
+    }
+
+    let Ok(permit) = state.jobs.clone().try_acquire_owned() else {
+        return retry_after_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            state.retry_after,
+            "server is busy",
+        );
+    };
+    let deadline = state
+        .compute_timeout
+        .map(|compute_timeout| Instant::now() + compute_timeout);
+
+    let (sender, receiver) = mpsc::channel::<String>(64);
+
+    let span = Span::current();
+    tokio::task::spawn_blocking(move || {
+        let _guard = span.enter();
+        let cache: Cache = papaya::HashMap::with_hasher(FxBuildHasher);
+        // set once the deadline passes or the client goes away; every worker
+        // checks it before starting on a game
+        let cancelled = AtomicBool::new(false);
+        let timed_out = AtomicBool::new(false);
+        let (lines, finished_lines) = std::sync::mpsc::channel::<String>();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                games
+                    .into_par_iter()
+                    .enumerate()
+                    .for_each_with(lines, |lines, (index, value)| {
+                        let _guard = span.enter();
+                        if cancelled.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                            timed_out.store(true, Ordering::Relaxed);
+                            cancelled.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                        let line = match parse_batch_game(value) {
+                            Ok((_, _, analysis, projection))
+                                if (analysis || projection) && projections.is_none() =>
+                            {
+                                json!({
+                                    "index": index,
+                                    "errors": [ANALYSIS_UNAVAILABLE],
+                                })
+                            }
+                            Ok((game, solver, analysis, projection)) => {
+                                let mut line = json!({
+                                    "index": index,
+                                    "choices": choices_to_json(
+                                        game,
+                                        advise(
+                                            game,
+                                            solver,
+                                            &expected_values,
+                                            &cache,
+                                            state.exact_solver,
+                                            state.slow_solver_threshold,
+                                        ),
+                                    ),
+                                });
+                                if let Some(projections) = projections.as_ref().filter(|_| analysis)
+                                {
+                                    line["analysis"] =
+                                        json!(analyze(game, &expected_values, projections));
+                                }
+                                if let Some(projections) =
+                                    projections.as_ref().filter(|_| projection)
+                                {
+                                    line["projection"] = projection_to_json(project(
+                                        game,
+                                        &expected_values,
+                                        projections,
+                                    ));
+                                }
+                                line
+                            }
+                            Err(error) => {
+                                METRICS.observe_validation_failure();
+                                json!({
+                                    "index": index,
+                                    "errors": [error],
+                                })
+                            }
+                        };
+                        _ = lines.send(format!("{line}\n"));
+                    });
+            });
+            // the rayon workers never wait on the client; only this thread
+            // does, and it stops the batch once the receiver is dropped
+            for line in finished_lines {
+                if sender.blocking_send(line).is_err() {
+                    cancelled.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+        });
+        if timed_out.load(Ordering::Relaxed) {
+            tracing::warn!("batch computation timed out");
+            let line = json!({ "errors": ["computation timed out"] });
+            _ = sender.blocking_send(format!("{line}\n"));
+        }
+        drop(permit);
+    });
 
+    let body = Body::from_stream(ReceiverStream::new(receiver).map(Ok::<_, Infallible>));
     (
-        AppendHeaders([(ACCESS_CONTROL_ALLOW_ORIGIN, "*")]),
-        Json(json!(choices_json)),
+        AppendHeaders([(CONTENT_TYPE, "application/x-ndjson")]),
+        body,
     )
+        .into_response()
 }
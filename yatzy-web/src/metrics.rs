@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+
+const SOLVER_DURATION_BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+lazy_static! {
+    pub static ref METRICS: Metrics = Metrics::new();
+}
+
+#[derive(Debug)]
+struct Histogram {
+    buckets: &'static [f64],
+    bucket_counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            bucket_counts: buckets.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, bucket_count) in self.buckets.iter().zip(&self.bucket_counts) {
+            if seconds <= *bucket {
+                bucket_count.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(
+            duration.as_micros().try_into().unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn render(&self, output: &mut String, name: &str, labels: &str) {
+        for (bucket, bucket_count) in self.buckets.iter().zip(&self.bucket_counts) {
+            _ = writeln!(
+                output,
+                "{name}_bucket{{{labels},le=\"{bucket}\"}} {}",
+                bucket_count.load(Ordering::Relaxed),
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        _ = writeln!(output, "{name}_bucket{{{labels},le=\"+Inf\"}} {count}");
+        _ = writeln!(
+            output,
+            "{name}_sum{{{labels}}} {}",
+            self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        );
+        _ = writeln!(output, "{name}_count{{{labels}}} {count}");
+    }
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    ready: AtomicBool,
+    reload_failures: AtomicU64,
+    reloads: AtomicU64,
+    requests: Mutex<BTreeMap<(String, u16), u64>>,
+    solver_durations: [Histogram; 3],
+    table_size: AtomicU64,
+    validation_failures: AtomicU64,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            ready: AtomicBool::new(false),
+            reload_failures: AtomicU64::new(0),
+            reloads: AtomicU64::new(0),
+            requests: Mutex::new(BTreeMap::new()),
+            solver_durations: [(); 3].map(|_| Histogram::new(&SOLVER_DURATION_BUCKETS)),
+            table_size: AtomicU64::new(0),
+            validation_failures: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub fn set_ready(&self, table_size: usize) {
+        self.table_size
+            .store(table_size.try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+        self.ready.store(true, Ordering::Release);
+    }
+
+    pub fn observe_cache_lookup(&self, hit: bool) {
+        if hit {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn observe_reload(&self, success: bool) {
+        if success {
+            self.reloads.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.reload_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn observe_request(&self, path: &str, status: u16) {
+        let mut requests = self.requests.lock().expect("metrics mutex poisoned");
+        *requests.entry((String::from(path), status)).or_insert(0) += 1;
+    }
+
+    pub fn observe_solver(&self, rerolls_left: u8, duration: Duration) {
+        self.solver_durations[usize::from(rerolls_left)].observe(duration);
+    }
+
+    pub fn observe_validation_failure(&self) {
+        self.validation_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("# HELP yatzy_ready Whether the expected values table has been loaded.\n");
+        output.push_str("# TYPE yatzy_ready gauge\n");
+        _ = writeln!(output, "yatzy_ready {}", u8::from(self.is_ready()));
+
+        output.push_str(
+            "# HELP yatzy_expected_values_table_size Number of game states in the expected values table.\n",
+        );
+        output.push_str("# TYPE yatzy_expected_values_table_size gauge\n");
+        _ = writeln!(
+            output,
+            "yatzy_expected_values_table_size {}",
+            self.table_size.load(Ordering::Relaxed),
+        );
+
+        output.push_str(
+            "# HELP yatzy_expected_values_reloads_total Expected values table reloads by result.\n",
+        );
+        output.push_str("# TYPE yatzy_expected_values_reloads_total counter\n");
+        _ = writeln!(
+            output,
+            "yatzy_expected_values_reloads_total{{result=\"success\"}} {}",
+            self.reloads.load(Ordering::Relaxed),
+        );
+        _ = writeln!(
+            output,
+            "yatzy_expected_values_reloads_total{{result=\"failure\"}} {}",
+            self.reload_failures.load(Ordering::Relaxed),
+        );
+
+        output.push_str("# HELP yatzy_http_requests_total HTTP requests by route and status.\n");
+        output.push_str("# TYPE yatzy_http_requests_total counter\n");
+        for ((path, status), count) in self.requests.lock().expect("metrics mutex poisoned").iter()
+        {
+            _ = writeln!(
+                output,
+                "yatzy_http_requests_total{{path=\"{path}\",status=\"{status}\"}} {count}",
+            );
+        }
+
+        output.push_str(
+            "# HELP yatzy_validation_failures_total Games rejected because of invalid input.\n",
+        );
+        output.push_str("# TYPE yatzy_validation_failures_total counter\n");
+        _ = writeln!(
+            output,
+            "yatzy_validation_failures_total {}",
+            self.validation_failures.load(Ordering::Relaxed),
+        );
+
+        output.push_str(
+            "# HELP yatzy_solver_duration_seconds Time spent computing the best choices.\n",
+        );
+        output.push_str("# TYPE yatzy_solver_duration_seconds histogram\n");
+        for (rerolls_left, histogram) in self.solver_durations.iter().enumerate() {
+            histogram.render(
+                &mut output,
+                "yatzy_solver_duration_seconds",
+                &format!("rerolls_left=\"{rerolls_left}\""),
+            );
+        }
+
+        let hits = self.cache_hits.load(Ordering::Relaxed);
+        let misses = self.cache_misses.load(Ordering::Relaxed);
+        output.push_str(
+            "# HELP yatzy_solver_cache_hits_total Positions answered from the solver cache.\n",
+        );
+        output.push_str("# TYPE yatzy_solver_cache_hits_total counter\n");
+        _ = writeln!(output, "yatzy_solver_cache_hits_total {hits}");
+        output.push_str(
+            "# HELP yatzy_solver_cache_misses_total Positions the solver had to evaluate.\n",
+        );
+        output.push_str("# TYPE yatzy_solver_cache_misses_total counter\n");
+        _ = writeln!(output, "yatzy_solver_cache_misses_total {misses}");
+        output.push_str(
+            "# HELP yatzy_solver_cache_hit_ratio Fraction of positions answered from the solver cache.\n",
+        );
+        output.push_str("# TYPE yatzy_solver_cache_hit_ratio gauge\n");
+        let ratio = if hits + misses == 0 {
+            0.0
+        } else {
+            hits as f64 / (hits + misses) as f64
+        };
+        _ = writeln!(output, "yatzy_solver_cache_hit_ratio {ratio}");
+
+        output
+    }
+}
@@ -0,0 +1,105 @@
+use num_traits::ToPrimitive as _;
+use rand::SeedableRng as _;
+use rand_chacha::ChaCha8Rng;
+use rustc_hash::FxBuildHasher;
+use serde::Deserialize;
+use yatzy::Game;
+use yatzy_solver::{
+    Choice, evaluate_choices,
+    noisy::{Noise, sample_choice},
+    turn::TurnTable,
+};
+
+use crate::{Cache, table::ExpectedValues};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolverMode {
+    #[default]
+    Optimal,
+    Softmax,
+    BoundedLoss,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Solver {
+    pub noise: Option<Noise>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SolverError {
+    #[error("missing value for parameter `{0}`")]
+    MissingValue(&'static str),
+    #[error("invalid value for parameter `{0}`")]
+    InvalidValue(&'static str),
+    #[error("parameter `{0}` is not used by the selected mode")]
+    UnusedParameter(&'static str),
+}
+
+impl Solver {
+    pub fn new(
+        mode: SolverMode,
+        temperature: Option<f64>,
+        max_loss: Option<f64>,
+        seed: Option<u64>,
+    ) -> Result<Self, SolverError> {
+        let noise = match mode {
+            SolverMode::Optimal => {
+                if temperature.is_some() {
+                    return Err(SolverError::UnusedParameter("temperature"));
+                }
+                if max_loss.is_some() {
+                    return Err(SolverError::UnusedParameter("max_loss"));
+                }
+                None
+            }
+            SolverMode::Softmax => {
+                if max_loss.is_some() {
+                    return Err(SolverError::UnusedParameter("max_loss"));
+                }
+                let temperature = temperature.ok_or(SolverError::MissingValue("temperature"))?;
+                Some(
+                    Noise::softmax(temperature)
+                        .map_err(|_| SolverError::InvalidValue("temperature"))?,
+                )
+            }
+            SolverMode::BoundedLoss => {
+                if temperature.is_some() {
+                    return Err(SolverError::UnusedParameter("temperature"));
+                }
+                let max_loss = max_loss.ok_or(SolverError::MissingValue("max_loss"))?;
+                Some(
+                    Noise::bounded_loss(max_loss)
+                        .map_err(|_| SolverError::InvalidValue("max_loss"))?,
+                )
+            }
+        };
+        if noise.is_none() && seed.is_some() {
+            return Err(SolverError::UnusedParameter("seed"));
+        }
+        Ok(Self { noise, seed })
+    }
+}
+
+pub fn noisy_choice(
+    game: Game,
+    noise: Noise,
+    seed: Option<u64>,
+    exact: bool,
+    expected_values: &ExpectedValues,
+    cache: &Cache,
+) -> Choice {
+    let evaluations: Vec<(Choice, f64)> = if exact {
+        evaluate_choices::<_, FxBuildHasher, _, _>(game, expected_values, cache)
+            .into_iter()
+            .map(|(choice, value)| (choice, value.to_f64().unwrap()))
+            .collect()
+    } else {
+        TurnTable::new(game, expected_values).evaluate(game.dice(), game.rerolls_left())
+    };
+    match seed {
+        Some(seed) => sample_choice(&evaluations, noise, &mut ChaCha8Rng::seed_from_u64(seed)),
+        None => sample_choice(&evaluations, noise, &mut rand::rng()),
+    }
+}
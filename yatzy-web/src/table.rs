@@ -0,0 +1,151 @@
+use std::{
+    io,
+    path::Path,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use num_rational::Ratio;
+use num_traits::ToPrimitive as _;
+use rustc_hash::FxBuildHasher;
+use yatzy_solver::{
+    GameState, analysis,
+    table::{self, TableError},
+};
+
+use crate::metrics::METRICS;
+
+pub const STATE_COUNT: usize = 958_974;
+
+pub type ExpectedValues = papaya::HashMap<GameState, Ratio<BigUint>, FxBuildHasher>;
+pub type Projections = analysis::Projections<FxBuildHasher>;
+pub type Table = (Arc<ExpectedValues>, Option<Arc<Projections>>);
+
+lazy_static! {
+    // projections are optional and always belong to the table loaded with them
+    static ref EXPECTED_VALUES: RwLock<Option<Table>> = RwLock::new(None);
+}
+
+static LOADING: AtomicBool = AtomicBool::new(false);
+
+// held while a table is loaded, so that the initial load and reloads never
+// run at the same time
+pub struct LoadGuard(());
+
+impl Drop for LoadGuard {
+    fn drop(&mut self) {
+        LOADING.store(false, Ordering::Release);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("failed to read expected values: {0}")]
+    Read(#[from] io::Error),
+    #[error("failed to parse expected values: {0}")]
+    Parse(#[from] TableError),
+    #[error("expected {STATE_COUNT} game states, found {0}")]
+    StateCount(usize),
+    #[error("failed to read projections: {0}")]
+    ReadProjections(io::Error),
+    #[error("failed to parse projections: {0}")]
+    ParseProjections(postcard::Error),
+    #[error("expected {STATE_COUNT} projections, found {0}")]
+    ProjectionCount(usize),
+    #[error("projections were computed from a different table")]
+    ProjectionMismatch,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReloadError {
+    #[error("a reload is already in progress")]
+    InProgress,
+    #[error(transparent)]
+    Load(#[from] LoadError),
+}
+
+pub fn current() -> Option<Table> {
+    EXPECTED_VALUES
+        .read()
+        .expect("expected values lock poisoned")
+        .clone()
+}
+
+pub fn lock_loading() -> Result<LoadGuard, ReloadError> {
+    if LOADING.swap(true, Ordering::AcqRel) {
+        return Err(ReloadError::InProgress);
+    }
+    Ok(LoadGuard(()))
+}
+
+pub fn load(
+    path: &Path,
+    projections_path: Option<&Path>,
+    _guard: LoadGuard,
+) -> Result<usize, LoadError> {
+    let _span = tracing::info_span!("load_expected_values", path = %path.display()).entered();
+    let start = Instant::now();
+
+    let bytes = std::fs::read(path)?;
+    let expected_values: std::collections::HashMap<GameState, Ratio<BigUint>> =
+        table::decode(&bytes)?;
+    if expected_values.len() != STATE_COUNT {
+        return Err(LoadError::StateCount(expected_values.len()));
+    }
+
+    let projections = match projections_path {
+        Some(projections_path) => {
+            let bytes = std::fs::read(projections_path).map_err(LoadError::ReadProjections)?;
+            let (initial_value, projections): (f64, Projections) =
+                analysis::decode_projections(&bytes).map_err(LoadError::ParseProjections)?;
+            if projections.len() != STATE_COUNT {
+                return Err(LoadError::ProjectionCount(projections.len()));
+            }
+            let table_initial_value = expected_values
+                .get(&GameState::initial())
+                .and_then(|value| value.to_f64());
+            if table_initial_value != Some(initial_value) {
+                return Err(LoadError::ProjectionMismatch);
+            }
+            Some(Arc::new(projections))
+        }
+        None => None,
+    };
+
+    let table = papaya::HashMap::with_capacity_and_hasher(STATE_COUNT, FxBuildHasher);
+    {
+        let table = table.pin();
+        for (state, value) in expected_values {
+            table.insert(state, value);
+        }
+    }
+
+    // requests that already hold the old table keep using it until they finish
+    let previous = EXPECTED_VALUES
+        .write()
+        .expect("expected values lock poisoned")
+        .replace((Arc::new(table), projections));
+    drop(previous);
+    METRICS.set_ready(STATE_COUNT);
+    tracing::info!(
+        states = STATE_COUNT,
+        elapsed_ms = start.elapsed().as_millis(),
+        "loaded expected values",
+    );
+    Ok(STATE_COUNT)
+}
+
+pub fn reload(path: &Path, projections_path: Option<&Path>) -> Result<usize, ReloadError> {
+    let guard = lock_loading()?;
+    let result = load(path, projections_path, guard);
+    METRICS.observe_reload(result.is_ok());
+    if let Err(error) = &result {
+        tracing::error!("failed to reload expected values, keeping the current table: {error}");
+    }
+    Ok(result?)
+}
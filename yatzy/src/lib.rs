@@ -1,6 +1,8 @@
 use std::{
+    fmt,
     hash::{Hash, Hasher},
     ops::Deref,
+    str::FromStr,
 };
 
 use lazy_static::lazy_static;
@@ -9,6 +11,8 @@ use rand::{
     distr::{Distribution as _, Uniform},
 };
 
+pub mod transitions;
+
 pub type Die = u8;
 
 lazy_static! {
@@ -97,6 +101,10 @@ impl Dice {
         Ok(())
     }
 
+    pub fn index(&self) -> usize {
+        transitions::dice_index(&self.array).expect("invalid dice")
+    }
+
     pub fn reroll_all<R: Rng>(&mut self, rng: &mut R) {
         let mut array = [(); 5].map(|_| DISTRIBUTION.sample(rng));
         array.sort_unstable();
@@ -241,6 +249,67 @@ impl Combo {
     }
 }
 
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Ones => "ones",
+            Self::Twos => "twos",
+            Self::Threes => "threes",
+            Self::Fours => "fours",
+            Self::Fives => "fives",
+            Self::Sixes => "sixes",
+            Self::OnePair => "one pair",
+            Self::TwoPairs => "two pairs",
+            Self::ThreeOfAKind => "three of a kind",
+            Self::FourOfAKind => "four of a kind",
+            Self::SmallStraight => "small straight",
+            Self::LargeStraight => "large straight",
+            Self::FullHouse => "full house",
+            Self::Chance => "chance",
+            Self::Yatzy => "yatzy",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseComboError {
+    #[error("unknown combo `{0}`")]
+    UnknownCombo(String),
+}
+
+impl FromStr for Combo {
+    type Err = ParseComboError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s
+            .trim()
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match name.as_str() {
+            "ones" | "1s" => Ok(Self::Ones),
+            "twos" | "2s" => Ok(Self::Twos),
+            "threes" | "3s" => Ok(Self::Threes),
+            "fours" | "4s" => Ok(Self::Fours),
+            "fives" | "5s" => Ok(Self::Fives),
+            "sixes" | "6s" => Ok(Self::Sixes),
+            "one pair" | "pair" | "1p" => Ok(Self::OnePair),
+            "two pairs" | "2p" => Ok(Self::TwoPairs),
+            "three of a kind" | "3k" => Ok(Self::ThreeOfAKind),
+            "four of a kind" | "4k" => Ok(Self::FourOfAKind),
+            "small straight" | "ss" => Ok(Self::SmallStraight),
+            "large straight" | "ls" => Ok(Self::LargeStraight),
+            "full house" | "fh" => Ok(Self::FullHouse),
+            "chance" | "c" => Ok(Self::Chance),
+            "yatzy" | "y" => Ok(Self::Yatzy),
+            _ => Err(ParseComboError::UnknownCombo(String::from(s.trim()))),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct ComboIterator {
     current: Option<Combo>,
@@ -686,12 +755,40 @@ impl Game {
         }
     }
 
+    pub fn set_dice_raw(&mut self, dice: Dice) {
+        self.dice = dice;
+    }
+
     pub fn set_rerolls(&mut self, rerolls_left: u8) {
         assert!(rerolls_left <= 2);
         self.rerolls_left = rerolls_left;
     }
 }
 
+impl From<Game> for GameOptions {
+    fn from(game: Game) -> Self {
+        Self {
+            dice: *game.dice,
+            rerolls_left: game.rerolls_left,
+            ones: game.ones,
+            twos: game.twos,
+            threes: game.threes,
+            fours: game.fours,
+            fives: game.fives,
+            sixes: game.sixes,
+            one_pair: game.one_pair,
+            two_pairs: game.two_pairs,
+            three_of_a_kind: game.three_of_a_kind,
+            four_of_a_kind: game.four_of_a_kind,
+            small_straight: game.small_straight,
+            large_straight: game.large_straight,
+            full_house: game.full_house,
+            chance: game.chance,
+            yatzy: game.yatzy,
+        }
+    }
+}
+
 fn print_score(name: &'static str, score: Option<u8>) {
     match score {
         Some(score) => {
@@ -0,0 +1,120 @@
+use itertools::Itertools as _;
+use lazy_static::lazy_static;
+use num_rational::Ratio;
+
+use crate::{Dice, Die};
+
+pub const DICE_COUNT: usize = 252;
+pub const KEEP_COUNT: usize = 462;
+
+// counts per face encoded in base 6, used to look up multiset indices
+const CODE_COUNT: usize = 6 * 6 * 6 * 6 * 6 * 6;
+const NO_INDEX: u16 = u16::MAX;
+
+lazy_static! {
+    static ref KEEPS: Vec<Vec<Die>> = (0..=5)
+        .flat_map(|n| (1..=6).combinations_with_replacement(n))
+        .collect();
+    static ref KEEP_INDICES: Vec<u16> = {
+        let mut indices = vec![NO_INDEX; CODE_COUNT];
+        for (index, keep) in KEEPS.iter().enumerate() {
+            indices[code(keep).unwrap()] = index as u16;
+        }
+        indices
+    };
+    static ref DICE: Vec<Dice> = KEEPS[KEEP_COUNT - DICE_COUNT..]
+        .iter()
+        .map(|dice| Dice::new_raw(dice.as_slice().try_into().unwrap()))
+        .collect();
+    static ref TRANSITIONS: Vec<Vec<(usize, Ratio<u16>)>> = KEEPS
+        .iter()
+        .map(|keep| {
+            let n = 5 - keep.len();
+            let total = 6_u16.pow(n as u32);
+            (1..=6)
+                .combinations_with_replacement(n)
+                .map(|roll| {
+                    let mut permutations = factorial(n);
+                    for face in 1..=6 {
+                        permutations /= factorial(roll.iter().filter(|&&die| die == face).count());
+                    }
+                    let mut outcome = keep.clone();
+                    outcome.extend(roll);
+                    (
+                        dice_index(&outcome).unwrap(),
+                        Ratio::new(permutations, total),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    static ref SUB_KEEPS: Vec<Vec<usize>> = DICE
+        .iter()
+        .map(|dice| {
+            (0..=5)
+                .flat_map(|n| dice.iter().copied().combinations(n))
+                .map(|keep| keep_index(&keep).unwrap())
+                .unique()
+                .collect()
+        })
+        .collect();
+}
+
+fn factorial(n: usize) -> u16 {
+    (1..=n as u16).product()
+}
+
+fn code(dice: &[Die]) -> Option<usize> {
+    if dice.len() > 5 {
+        return None;
+    }
+    let mut counts = [0; 6];
+    for &die in dice {
+        if !(1..=6).contains(&die) {
+            return None;
+        }
+        counts[usize::from(die - 1)] += 1;
+    }
+    Some(counts.iter().fold(0, |code, &count| code * 6 + count))
+}
+
+pub fn dice_index(dice: &[Die]) -> Option<usize> {
+    if dice.len() != 5 {
+        return None;
+    }
+    keep_index(dice).map(|index| index - (KEEP_COUNT - DICE_COUNT))
+}
+
+pub fn dice_from_index(index: usize) -> Dice {
+    DICE[index]
+}
+
+pub fn all_dice() -> &'static [Dice] {
+    &DICE
+}
+
+pub fn keep_index(dice: &[Die]) -> Option<usize> {
+    match KEEP_INDICES[code(dice)?] {
+        NO_INDEX => None,
+        index => Some(usize::from(index)),
+    }
+}
+
+pub fn keep_from_index(index: usize) -> &'static [Die] {
+    &KEEPS[index]
+}
+
+pub fn keep_len(index: usize) -> usize {
+    KEEPS[index].len()
+}
+
+// the five-dice multisets the kept dice can turn into, each with the
+// probability of rolling it; keeping all five dice leads back to the same hand
+pub fn transitions(keep_index: usize) -> &'static [(usize, Ratio<u16>)] {
+    &TRANSITIONS[keep_index]
+}
+
+// every distinct keep index for a hand, from keeping nothing up to the whole hand
+pub fn keeps(dice_index: usize) -> &'static [usize] {
+    &SUB_KEEPS[dice_index]
+}